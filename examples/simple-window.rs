@@ -1,6 +1,5 @@
 use rich_sdl2_rust::{
     color::Rgb,
-    renderer::pen::Pen,
     ttf::{
         font::{pen, Font},
         Ttf,
@@ -34,14 +33,15 @@ pub fn main() {
 
     while !exit.get() {
         use pen::{FontRenderExt, FontRenderOptions};
-        let pen = Pen::new(&renderer);
-        pen.set_color(Rgb {
+        let frame = renderer.frame(Some(Rgb {
             r: 127,
             g: 127,
             b: 0,
-        });
-        pen.clear();
-        pen.text(&font, &message.borrow(), FontRenderOptions::default());
+        }));
+        frame
+            .pen()
+            .text(&font, &message.borrow(), FontRenderOptions::default());
+        drop(frame);
 
         event.poll();
     }
@@ -12,6 +12,7 @@ use self::{
     joystick::event::JoystickEvent,
     keyboard::KeyboardEvent,
     mouse::{MouseButtonEvent, MouseEvent, MouseMotionEvent, MouseWheelEvent},
+    render::RenderEvent,
     text::{TextEditingEvent, TextInputEvent},
     touch::gesture::GestureEvent,
     window::WindowEvent,
@@ -25,6 +26,7 @@ pub mod game_controller;
 pub mod joystick;
 pub mod keyboard;
 pub mod mouse;
+pub mod render;
 pub mod sensor;
 pub mod text;
 pub mod touch;
@@ -64,6 +66,7 @@ pub struct EventBox<'video> {
     audio_device_event_handlers: EventHandlers<'video, AudioDeviceEvent>,
     drop_event_handlers: EventHandlers<'video, DropEvent>,
     gesture_event_handlers: EventHandlers<'video, GestureEvent>,
+    render_event_handlers: EventHandlers<'video, RenderEvent>,
     _phantom: PhantomData<&'video ()>,
 }
 
@@ -89,6 +92,7 @@ impl<'video> EventBox<'video> {
             audio_device_event_handlers: EventHandlers::default(),
             drop_event_handlers: EventHandlers::default(),
             gesture_event_handlers: EventHandlers::default(),
+            render_event_handlers: EventHandlers::default(),
             _phantom: PhantomData,
         }
     }
@@ -148,6 +152,11 @@ impl<'video> EventBox<'video> {
         self.gesture_event_handlers.push(handler);
     }
 
+    /// Registers the handler to handle [`RenderEvent`].
+    pub fn handle_render(&mut self, handler: EventHandler<'video, RenderEvent>) {
+        self.render_event_handlers.push(handler);
+    }
+
     fn handle_event(&self, event: bind::SDL_Event) {
         let ty = unsafe { event.type_ };
         match ty as EnumInt {
@@ -239,6 +248,10 @@ impl<'video> EventBox<'video> {
                 let gesture = unsafe { event.dgesture }.into();
                 self.gesture_event_handlers.call_handlers(&gesture);
             }
+            bind::SDL_RENDER_TARGETS_RESET | bind::SDL_RENDER_DEVICE_RESET => {
+                let render = unsafe { event.common }.into();
+                self.render_event_handlers.call_handlers(&render);
+            }
             _ => {}
         }
     }
@@ -0,0 +1,33 @@
+//! Events on the renderer, resetting its resources.
+
+use crate::{bind, EnumInt};
+
+/// An event occurred when the resources of the renderer were lost.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum RenderEvent {
+    /// The render targets were reset, so their contents must be drawn again.
+    TargetsReset {
+        /// When this event occurred.
+        timestamp: u32,
+    },
+    /// The render device was reset, so all the textures must be created again.
+    DeviceReset {
+        /// When this event occurred.
+        timestamp: u32,
+    },
+}
+
+impl From<bind::SDL_CommonEvent> for RenderEvent {
+    fn from(raw: bind::SDL_CommonEvent) -> Self {
+        match raw.type_ as EnumInt {
+            bind::SDL_RENDER_TARGETS_RESET => Self::TargetsReset {
+                timestamp: raw.timestamp,
+            },
+            bind::SDL_RENDER_DEVICE_RESET => Self::DeviceReset {
+                timestamp: raw.timestamp,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
@@ -135,6 +135,6 @@ impl FontRenderExt for Pen<'_> {
             .rendered_size(text)
             .expect("calculating text size failed");
         let up_left = options.aligned_pos(size);
-        Paster::new(self.renderer()).paste(&texture, Some(Rect { up_left, size }));
+        Paster::view(self.renderer()).paste(&texture, Some(Rect { up_left, size }));
     }
 }
@@ -2,8 +2,11 @@
 
 use crate::bind;
 
+pub mod blend;
 pub mod pixel;
 
+use blend::CustomBlendMode;
+
 /// A RGB color structure.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[must_use]
@@ -57,6 +60,12 @@ impl From<Rgba> for bind::SDL_Color {
     }
 }
 
+impl From<bind::SDL_Color> for Rgba {
+    fn from(bind::SDL_Color { r, g, b, a }: bind::SDL_Color) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
 /// A mode for blending colors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[must_use]
@@ -70,15 +79,24 @@ pub enum BlendMode {
     Add,
     /// Blending colors by multiplication.
     Mul,
+    /// Blending colors by multiplication, and by the alpha value of the source color.
+    Multiply,
+    /// Blending colors by the custom factors and operations. This is only for renderers and textures, and some renderers do not support this.
+    Custom(CustomBlendMode),
 }
 
 impl From<bind::SDL_BlendMode> for BlendMode {
     fn from(raw: bind::SDL_BlendMode) -> Self {
         match raw {
+            bind::SDL_BLENDMODE_NONE => BlendMode::None,
             bind::SDL_BLENDMODE_BLEND => BlendMode::AlphaBlend,
             bind::SDL_BLENDMODE_ADD => BlendMode::Add,
             bind::SDL_BLENDMODE_MOD => BlendMode::Mul,
-            _ => BlendMode::None,
+            bind::SDL_BLENDMODE_MUL => BlendMode::Multiply,
+            _ => BlendMode::Custom(
+                CustomBlendMode::from_raw(raw)
+                    .unwrap_or_else(|| panic!("unknown blend mode: {:#x}", raw)),
+            ),
         }
     }
 }
@@ -89,7 +107,9 @@ impl From<BlendMode> for bind::SDL_BlendMode {
             BlendMode::AlphaBlend => bind::SDL_BLENDMODE_BLEND,
             BlendMode::Add => bind::SDL_BLENDMODE_ADD,
             BlendMode::Mul => bind::SDL_BLENDMODE_MOD,
+            BlendMode::Multiply => bind::SDL_BLENDMODE_MUL,
             BlendMode::None => bind::SDL_BLENDMODE_NONE,
+            BlendMode::Custom(custom) => custom.as_raw(),
         }
     }
 }
@@ -0,0 +1,158 @@
+//! Custom blend modes composed from factors and operations.
+
+use typed_builder::TypedBuilder;
+
+use crate::{bind, EnumInt};
+
+/// A factor multiplied into the source or destination color on blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlendFactor {
+    /// `(0, 0, 0, 0)`.
+    Zero,
+    /// `(1, 1, 1, 1)`.
+    One,
+    /// `(srcR, srcG, srcB, srcA)`.
+    SrcColor,
+    /// `(1-srcR, 1-srcG, 1-srcB, 1-srcA)`.
+    OneMinusSrcColor,
+    /// `(srcA, srcA, srcA, srcA)`.
+    SrcAlpha,
+    /// `(1-srcA, 1-srcA, 1-srcA, 1-srcA)`.
+    OneMinusSrcAlpha,
+    /// `(dstR, dstG, dstB, dstA)`.
+    DstColor,
+    /// `(1-dstR, 1-dstG, 1-dstB, 1-dstA)`.
+    OneMinusDstColor,
+    /// `(dstA, dstA, dstA, dstA)`.
+    DstAlpha,
+    /// `(1-dstA, 1-dstA, 1-dstA, 1-dstA)`.
+    OneMinusDstAlpha,
+}
+
+impl BlendFactor {
+    fn from_raw(raw: EnumInt) -> Option<Self> {
+        Some(match raw {
+            bind::SDL_BLENDFACTOR_ZERO => BlendFactor::Zero,
+            bind::SDL_BLENDFACTOR_ONE => BlendFactor::One,
+            bind::SDL_BLENDFACTOR_SRC_COLOR => BlendFactor::SrcColor,
+            bind::SDL_BLENDFACTOR_ONE_MINUS_SRC_COLOR => BlendFactor::OneMinusSrcColor,
+            bind::SDL_BLENDFACTOR_SRC_ALPHA => BlendFactor::SrcAlpha,
+            bind::SDL_BLENDFACTOR_ONE_MINUS_SRC_ALPHA => BlendFactor::OneMinusSrcAlpha,
+            bind::SDL_BLENDFACTOR_DST_COLOR => BlendFactor::DstColor,
+            bind::SDL_BLENDFACTOR_ONE_MINUS_DST_COLOR => BlendFactor::OneMinusDstColor,
+            bind::SDL_BLENDFACTOR_DST_ALPHA => BlendFactor::DstAlpha,
+            bind::SDL_BLENDFACTOR_ONE_MINUS_DST_ALPHA => BlendFactor::OneMinusDstAlpha,
+            _ => return None,
+        })
+    }
+
+    fn as_raw(self) -> bind::SDL_BlendFactor {
+        match self {
+            BlendFactor::Zero => bind::SDL_BLENDFACTOR_ZERO,
+            BlendFactor::One => bind::SDL_BLENDFACTOR_ONE,
+            BlendFactor::SrcColor => bind::SDL_BLENDFACTOR_SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => bind::SDL_BLENDFACTOR_ONE_MINUS_SRC_COLOR,
+            BlendFactor::SrcAlpha => bind::SDL_BLENDFACTOR_SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => bind::SDL_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstColor => bind::SDL_BLENDFACTOR_DST_COLOR,
+            BlendFactor::OneMinusDstColor => bind::SDL_BLENDFACTOR_ONE_MINUS_DST_COLOR,
+            BlendFactor::DstAlpha => bind::SDL_BLENDFACTOR_DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => bind::SDL_BLENDFACTOR_ONE_MINUS_DST_ALPHA,
+        }
+    }
+}
+
+/// An operation to combine the source and destination color on blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlendOperation {
+    /// `dst + src`, supported by all renderers.
+    Add,
+    /// `dst - src`, supported by D3D9, D3D11, OpenGL and OpenGLES.
+    Subtract,
+    /// `src - dst`, supported by D3D9, D3D11, OpenGL and OpenGLES.
+    RevSubtract,
+    /// `min(dst, src)`, supported by D3D11.
+    Minimum,
+    /// `max(dst, src)`, supported by D3D11.
+    Maximum,
+}
+
+impl BlendOperation {
+    fn from_raw(raw: EnumInt) -> Option<Self> {
+        Some(match raw {
+            bind::SDL_BLENDOPERATION_ADD => BlendOperation::Add,
+            bind::SDL_BLENDOPERATION_SUBTRACT => BlendOperation::Subtract,
+            bind::SDL_BLENDOPERATION_REV_SUBTRACT => BlendOperation::RevSubtract,
+            bind::SDL_BLENDOPERATION_MINIMUM => BlendOperation::Minimum,
+            bind::SDL_BLENDOPERATION_MAXIMUM => BlendOperation::Maximum,
+            _ => return None,
+        })
+    }
+
+    fn as_raw(self) -> bind::SDL_BlendOperation {
+        match self {
+            BlendOperation::Add => bind::SDL_BLENDOPERATION_ADD,
+            BlendOperation::Subtract => bind::SDL_BLENDOPERATION_SUBTRACT,
+            BlendOperation::RevSubtract => bind::SDL_BLENDOPERATION_REV_SUBTRACT,
+            BlendOperation::Minimum => bind::SDL_BLENDOPERATION_MINIMUM,
+            BlendOperation::Maximum => bind::SDL_BLENDOPERATION_MAXIMUM,
+        }
+    }
+}
+
+/// A custom blend mode, calculates `dstRGB = colorOperation(srcRGB * srcColorFactor, dstRGB * dstColorFactor)` and `dstA = alphaOperation(srcA * srcAlphaFactor, dstA * dstAlphaFactor)`.
+///
+/// The default values of the builder make the same as the alpha blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TypedBuilder)]
+pub struct CustomBlendMode {
+    /// The factor multiplied into the source color components.
+    #[builder(default = BlendFactor::SrcAlpha)]
+    pub src_color_factor: BlendFactor,
+    /// The factor multiplied into the destination color components.
+    #[builder(default = BlendFactor::OneMinusSrcAlpha)]
+    pub dst_color_factor: BlendFactor,
+    /// The operation to combine the color components.
+    #[builder(default = BlendOperation::Add)]
+    pub color_operation: BlendOperation,
+    /// The factor multiplied into the source alpha component.
+    #[builder(default = BlendFactor::One)]
+    pub src_alpha_factor: BlendFactor,
+    /// The factor multiplied into the destination alpha component.
+    #[builder(default = BlendFactor::OneMinusSrcAlpha)]
+    pub dst_alpha_factor: BlendFactor,
+    /// The operation to combine the alpha component.
+    #[builder(default = BlendOperation::Add)]
+    pub alpha_operation: BlendOperation,
+}
+
+impl CustomBlendMode {
+    /// Decomposes the raw blend mode composed by `SDL_ComposeCustomBlendMode`.
+    #[allow(clippy::unnecessary_cast)]
+    pub(super) fn from_raw(raw: bind::SDL_BlendMode) -> Option<Self> {
+        let raw = raw as u32;
+        let part = |shift: u32| ((raw >> shift) & 0xf) as EnumInt;
+        Some(Self {
+            src_color_factor: BlendFactor::from_raw(part(4))?,
+            dst_color_factor: BlendFactor::from_raw(part(8))?,
+            color_operation: BlendOperation::from_raw(part(0))?,
+            src_alpha_factor: BlendFactor::from_raw(part(20))?,
+            dst_alpha_factor: BlendFactor::from_raw(part(24))?,
+            alpha_operation: BlendOperation::from_raw(part(16))?,
+        })
+    }
+
+    pub(super) fn as_raw(self) -> bind::SDL_BlendMode {
+        unsafe {
+            bind::SDL_ComposeCustomBlendMode(
+                self.src_color_factor.as_raw(),
+                self.dst_color_factor.as_raw(),
+                self.color_operation.as_raw(),
+                self.src_alpha_factor.as_raw(),
+                self.dst_alpha_factor.as_raw(),
+                self.alpha_operation.as_raw(),
+            )
+        }
+    }
+}
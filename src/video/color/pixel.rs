@@ -1,4 +1,5 @@
 //! Pixel definitions for operating colors.
+use std::mem::ManuallyDrop;
 use std::ptr::NonNull;
 
 use kind::PixelFormatKind;
@@ -8,6 +9,7 @@ use static_assertions::assert_not_impl_all;
 use crate::color::{Rgb, Rgba};
 use crate::{bind, EnumInt, Result, Sdl, SdlError};
 
+pub mod convert;
 pub mod kind;
 pub mod layout;
 pub mod order;
@@ -27,6 +29,58 @@ impl Pixel {
     pub fn as_u32(self) -> u32 {
         self.pixel
     }
+
+    pub(crate) fn from_u32(pixel: u32) -> Self {
+        Self { pixel }
+    }
+
+    /// Constructs the pixel of the index in an indexed bitmap format.
+    pub fn from_index(index: u8) -> Self {
+        Self {
+            pixel: index as u32,
+        }
+    }
+
+    /// Reads a pixel from raw bytes in native byte order. The length of `bytes` must be the bytes per pixel, from 1 to 4.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> Self {
+        let mut buf = [0u8; 4];
+        let pixel = match bytes.len() {
+            1 => bytes[0] as u32,
+            2 => u16::from_ne_bytes([bytes[0], bytes[1]]) as u32,
+            3 => {
+                if cfg!(target_endian = "little") {
+                    buf[..3].copy_from_slice(bytes);
+                } else {
+                    buf[1..].copy_from_slice(bytes);
+                }
+                u32::from_ne_bytes(buf)
+            }
+            4 => {
+                buf.copy_from_slice(bytes);
+                u32::from_ne_bytes(buf)
+            }
+            _ => unreachable!("bytes per pixel must be in 1..=4"),
+        };
+        Self { pixel }
+    }
+
+    /// Writes the pixel into raw bytes in native byte order. The length of `bytes` must be the bytes per pixel, from 1 to 4.
+    pub(crate) fn write_bytes(self, bytes: &mut [u8]) {
+        let buf = self.pixel.to_ne_bytes();
+        match bytes.len() {
+            1 => bytes[0] = self.pixel as u8,
+            2 => bytes.copy_from_slice(&(self.pixel as u16).to_ne_bytes()),
+            3 => {
+                if cfg!(target_endian = "little") {
+                    bytes.copy_from_slice(&buf[..3]);
+                } else {
+                    bytes.copy_from_slice(&buf[1..]);
+                }
+            }
+            4 => bytes.copy_from_slice(&buf),
+            _ => unreachable!("bytes per pixel must be in 1..=4"),
+        }
+    }
 }
 
 /// A bit mask to extract a component.
@@ -87,6 +141,19 @@ impl PixelFormat {
         )
     }
 
+    /// Borrows the format owned by another object such as a surface, without touching its reference count. The returned format must not be dropped.
+    ///
+    /// # Safety
+    ///
+    /// `format` must be a valid pointer to the format allocated by SDL2, and must outlive the returned format.
+    pub(crate) unsafe fn borrowed(format: NonNull<bind::SDL_PixelFormat>) -> ManuallyDrop<Self> {
+        ManuallyDrop::new(Self { format })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut bind::SDL_PixelFormat {
+        self.format.as_ptr()
+    }
+
     /// Returns the kind of the format.
     #[must_use]
     pub fn kind(&self) -> PixelFormatKind {
@@ -0,0 +1,93 @@
+//! Converting raw pixels between pixel formats.
+
+use std::os::raw::c_int;
+
+use crate::geo::Size;
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::kind::PixelFormatKind;
+
+/// Asserts that the pixels in `pitch` cover `size` in the format, including the chroma planes of YUV formats.
+fn assert_covers(kind: PixelFormatKind, Size { width, height }: Size, pixels: &[u8], pitch: usize) {
+    assert!(kind.row_bytes(width) <= pitch, "pitch must cover a row");
+    assert!(
+        kind.required_len(height, pitch) <= pixels.len(),
+        "pixels must cover the size"
+    );
+}
+
+/// Converts the pixels of `size` in the source format into the destination format. Each argument is a tuple of the pixel format kind, pixels and pitch, which is the length of a row in bytes.
+///
+/// YUV formats such as `FourCode(*b"YV12")` are supported, then the pitch is of the Y plane.
+///
+/// # Errors
+///
+/// Returns `Err` if the conversion between the formats is unsupported.
+///
+/// # Panics
+///
+/// Panics if the pitches are shorter than a row, or the pixels are shorter than `size` needs.
+pub fn convert_pixels(
+    size: Size,
+    (src_kind, src, src_pitch): (PixelFormatKind, &[u8], usize),
+    (dst_kind, dst, dst_pitch): (PixelFormatKind, &mut [u8], usize),
+) -> Result<()> {
+    assert_covers(src_kind, size, src, src_pitch);
+    assert_covers(dst_kind, size, dst, dst_pitch);
+    let Size { width, height } = size;
+    let ret = unsafe {
+        bind::SDL_ConvertPixels(
+            width as c_int,
+            height as c_int,
+            src_kind.as_raw(),
+            src.as_ptr().cast(),
+            src_pitch as c_int,
+            dst_kind.as_raw(),
+            dst.as_mut_ptr().cast(),
+            dst_pitch as c_int,
+        )
+    };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(SdlError::Others { msg: Sdl::error() })
+    }
+}
+
+/// Premultiplies the alpha into the color components of the pixels, and writes them into the destination. The arguments are the same as [`convert_pixels`].
+///
+/// Note that SDL2 supports only ARGB8888 for both formats for now.
+///
+/// # Errors
+///
+/// Returns `Err` if the formats are unsupported.
+///
+/// # Panics
+///
+/// Panics if the pitches are shorter than a row, or the pixels are shorter than `size` needs.
+pub fn premultiply_alpha(
+    size: Size,
+    (src_kind, src, src_pitch): (PixelFormatKind, &[u8], usize),
+    (dst_kind, dst, dst_pitch): (PixelFormatKind, &mut [u8], usize),
+) -> Result<()> {
+    assert_covers(src_kind, size, src, src_pitch);
+    assert_covers(dst_kind, size, dst, dst_pitch);
+    let Size { width, height } = size;
+    let ret = unsafe {
+        bind::SDL_PremultiplyAlpha(
+            width as c_int,
+            height as c_int,
+            src_kind.as_raw(),
+            src.as_ptr().cast(),
+            src_pitch as c_int,
+            dst_kind.as_raw(),
+            dst.as_mut_ptr().cast(),
+            dst_pitch as c_int,
+        )
+    };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(SdlError::Others { msg: Sdl::error() })
+    }
+}
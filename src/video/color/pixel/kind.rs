@@ -106,9 +106,10 @@ impl PixelFormatKind {
             .unwrap_or_default()
     }
 
+    #[allow(clippy::unnecessary_cast)]
     pub(crate) fn from_raw(raw: EnumInt) -> Self {
         if (raw >> 28) & 0x0F != 1 {
-            let bytes = ((raw >> 24) & 0xf).to_le_bytes();
+            let bytes = (raw as u32).to_le_bytes();
             return PixelFormatKind::FourCode(bytes);
         }
         match (raw >> 24) & 0xf {
@@ -187,6 +188,41 @@ impl PixelFormatKind {
             PixelFormatKind::FourCode(bytes) => u32::from_le_bytes(bytes),
         }
     }
+
+    /// Returns whether the format is a planar YUV format, which has the chroma planes after the Y plane.
+    pub(crate) fn is_planar_yuv(self) -> bool {
+        matches!(
+            self,
+            PixelFormatKind::FourCode(code) if matches!(&code, b"YV12" | b"IYUV" | b"NV12" | b"NV21")
+        )
+    }
+
+    /// Returns the bytes of a row in `width` pixels, or of the Y plane for planar YUV formats.
+    pub(crate) fn row_bytes(self, width: u32) -> usize {
+        let width = width as usize;
+        match self {
+            PixelFormatKind::FourCode(code) if matches!(&code, b"YUY2" | b"UYVY" | b"YVYU") => {
+                (width + 1) / 2 * 4
+            }
+            PixelFormatKind::FourCode(_) => width,
+            _ => {
+                let bits_per_pixel = ((self.as_raw() >> 8) & 0xff) as usize;
+                (width * bits_per_pixel + 7) / 8
+            }
+        }
+    }
+
+    /// Returns the bytes needed for the pixels of `height` rows in `pitch` bytes, including the chroma planes of planar YUV formats.
+    pub(crate) fn required_len(self, height: u32, pitch: usize) -> usize {
+        let height = height as usize;
+        if self.is_planar_yuv() {
+            let chroma_pitch = pitch / 2 + pitch % 2;
+            let chroma_height = height / 2 + height % 2;
+            pitch * height + 2 * chroma_pitch * chroma_height
+        } else {
+            pitch * height
+        }
+    }
 }
 
 fn bits_per_packed_pixel(order: PackedPixelOrder, layout: PackedPixelLayout) -> u32 {
@@ -5,6 +5,8 @@ use crate::bind;
 /// A pixel order in a bitmap pixel format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BitmapPixelOrder {
+    /// No order, for the formats of a byte per pixel such as 8-bit indices.
+    None,
     /// An order in little endian.
     _4321,
     /// An order in big endian.
@@ -15,6 +17,7 @@ impl BitmapPixelOrder {
     #[allow(clippy::unnecessary_cast)]
     pub(super) fn as_raw(self) -> u32 {
         (match self {
+            BitmapPixelOrder::None => bind::SDL_BITMAPORDER_NONE,
             BitmapPixelOrder::_4321 => bind::SDL_BITMAPORDER_4321,
             BitmapPixelOrder::_1234 => bind::SDL_BITMAPORDER_1234,
         }) as u32
@@ -24,6 +27,7 @@ impl BitmapPixelOrder {
 impl From<bind::SDL_PixelFormatEnum> for BitmapPixelOrder {
     fn from(raw: bind::SDL_PixelFormatEnum) -> Self {
         match (raw >> 20) & 0xf {
+            bind::SDL_BITMAPORDER_NONE => BitmapPixelOrder::None,
             bind::SDL_BITMAPORDER_4321 => BitmapPixelOrder::_4321,
             bind::SDL_BITMAPORDER_1234 => BitmapPixelOrder::_1234,
             _ => unreachable!(),
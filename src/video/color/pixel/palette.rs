@@ -1,5 +1,6 @@
 //! Palettes for a bitmap pixel format.
 
+use std::ops::Range;
 use std::os::raw::c_int;
 use std::ptr::NonNull;
 
@@ -41,6 +42,16 @@ impl Palette {
         )
     }
 
+    /// Shares the palette owned by SDL2, such as the palette of a surface.
+    ///
+    /// # Safety
+    ///
+    /// `palette` must be a valid palette allocated by SDL2.
+    pub(crate) unsafe fn from_shared(palette: NonNull<bind::SDL_Palette>) -> Self {
+        (*palette.as_ptr()).refcount += 1;
+        Self { palette }
+    }
+
     pub(crate) fn as_ptr(&self) -> *mut bind::SDL_Palette {
         self.palette.as_ptr()
     }
@@ -66,6 +77,52 @@ impl Palette {
             Sdl::error_then_panic("Setting palette colors");
         }
     }
+
+    /// Returns the colors in the palette.
+    #[must_use]
+    pub fn colors(&self) -> Vec<Rgba> {
+        let raw = unsafe { self.palette.as_ref() };
+        unsafe { std::slice::from_raw_parts(raw.colors, self.num_colors()) }
+            .iter()
+            .map(|&color| color.into())
+            .collect()
+    }
+
+    /// Sets colors to the palette from the index `first`. The surfaces using this palette reflect them on the next blit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors` overflowed from the palette.
+    pub fn set_colors_from(&self, first: usize, colors: impl IntoIterator<Item = Rgba>) {
+        let colors: Vec<_> = colors.into_iter().map(Into::into).collect();
+        assert!(first + colors.len() <= self.num_colors());
+        let ret = unsafe {
+            bind::SDL_SetPaletteColors(
+                self.palette.as_ptr(),
+                colors.as_ptr(),
+                first as c_int,
+                colors.len() as c_int,
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Setting palette colors");
+        }
+    }
+
+    /// Rotates the colors in `range` by `steps` towards the larger index, for palette cycling effects.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` overflowed from the palette.
+    pub fn rotate_colors(&self, range: Range<usize>, steps: usize) {
+        let mut colors = self.colors()[range.clone()].to_vec();
+        if colors.is_empty() {
+            return;
+        }
+        let len = colors.len();
+        colors.rotate_right(steps % len);
+        self.set_colors_from(range.start, colors);
+    }
 }
 
 impl Drop for Palette {
@@ -2,8 +2,10 @@
 
 use crate::bind;
 
+mod float;
 mod rect;
 
+pub use float::*;
 pub use rect::*;
 
 /// A point of the cartesian coordinate system.
@@ -0,0 +1,329 @@
+use std::mem::MaybeUninit;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::{as_raw, bind};
+
+use super::{Line, Point, Rect, Size};
+
+/// A point of the cartesian coordinate system in floating-point, to render with sub-pixel precision.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+#[must_use]
+pub struct FPoint {
+    /// An x coordinate of the point.
+    pub x: f32,
+    /// An y coordinate of the point.
+    pub y: f32,
+}
+
+impl From<bind::SDL_FPoint> for FPoint {
+    fn from(bind::SDL_FPoint { x, y }: bind::SDL_FPoint) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<FPoint> for bind::SDL_FPoint {
+    fn from(FPoint { x, y }: FPoint) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Point> for FPoint {
+    fn from(Point { x, y }: Point) -> Self {
+        Self {
+            x: x as f32,
+            y: y as f32,
+        }
+    }
+}
+
+impl FPoint {
+    /// Calculates the new point with the offset.
+    pub fn offset(self, x: f32, y: f32) -> Self {
+        Self {
+            x: self.x + x,
+            y: self.y + y,
+        }
+    }
+
+    /// Rounds the coordinates into the nearest integer [`Point`].
+    pub fn round(self) -> Point {
+        Point {
+            x: self.x.round() as i32,
+            y: self.y.round() as i32,
+        }
+    }
+
+    /// Returns the length of the vector from the origin to the point.
+    #[must_use]
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns whether the point is in the rectangle.
+    #[must_use]
+    pub fn is_in(&self, rect: FRect) -> bool {
+        let bottom_right = rect.bottom_right();
+        rect.up_left.x <= self.x
+            && self.x <= bottom_right.x
+            && rect.up_left.y <= self.y
+            && self.y <= bottom_right.y
+    }
+}
+
+impl Add for FPoint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.offset(rhs.x, rhs.y)
+    }
+}
+
+impl AddAssign for FPoint {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for FPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.offset(-rhs.x, -rhs.y)
+    }
+}
+
+impl SubAssign for FPoint {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<f32> for FPoint {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl Div<f32> for FPoint {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl Neg for FPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// A rectangle in floating-point, holding up left point and size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+#[must_use]
+pub struct FRect {
+    /// A up left point of the rectangle.
+    pub up_left: FPoint,
+    /// A width of the rectangle.
+    pub width: f32,
+    /// A height of the rectangle.
+    pub height: f32,
+}
+
+impl From<bind::SDL_FRect> for FRect {
+    fn from(bind::SDL_FRect { x, y, w, h }: bind::SDL_FRect) -> Self {
+        Self {
+            up_left: FPoint { x, y },
+            width: w,
+            height: h,
+        }
+    }
+}
+
+impl From<FRect> for bind::SDL_FRect {
+    fn from(
+        FRect {
+            up_left,
+            width,
+            height,
+        }: FRect,
+    ) -> Self {
+        Self {
+            x: up_left.x,
+            y: up_left.y,
+            w: width,
+            h: height,
+        }
+    }
+}
+
+impl From<Rect> for FRect {
+    fn from(Rect { up_left, size }: Rect) -> Self {
+        Self {
+            up_left: up_left.into(),
+            width: size.width as f32,
+            height: size.height as f32,
+        }
+    }
+}
+
+impl FRect {
+    /// Constructs a rect from the center.
+    pub fn from_center(center: FPoint, width: f32, height: f32) -> Self {
+        Self {
+            up_left: FPoint {
+                x: center.x - width / 2.0,
+                y: center.y - height / 2.0,
+            },
+            width,
+            height,
+        }
+    }
+
+    /// Returns the bottom right point of the rectangle.
+    pub fn bottom_right(self) -> FPoint {
+        self.up_left.offset(self.width, self.height)
+    }
+    /// Returns the center point of the rectangle.
+    pub fn center(self) -> FPoint {
+        self.up_left.offset(self.width / 2.0, self.height / 2.0)
+    }
+
+    /// Calculates the new rect moved with the offset.
+    pub fn offset(self, x: f32, y: f32) -> Self {
+        Self {
+            up_left: self.up_left.offset(x, y),
+            ..self
+        }
+    }
+
+    /// Calculates the new rect scaled around the up left point.
+    pub fn scale(self, horizontal: f32, vertical: f32) -> Self {
+        Self {
+            width: self.width * horizontal,
+            height: self.height * vertical,
+            ..self
+        }
+    }
+
+    /// Rounds the rect into the nearest integer [`Rect`].
+    pub fn round(self) -> Rect {
+        let up_left = self.up_left.round();
+        let bottom_right = self.bottom_right().round();
+        Rect {
+            up_left,
+            size: Size {
+                width: (bottom_right.x - up_left.x).max(0) as u32,
+                height: (bottom_right.y - up_left.y).max(0) as u32,
+            },
+        }
+    }
+
+    /// Returns the enclosed rectangle of the points, with the clip region.
+    pub fn enclosed(points: impl IntoIterator<Item = FPoint>, clip: Option<FRect>) -> Option<Self> {
+        use std::os::raw::c_int;
+        let points: Vec<_> = points.into_iter().map(Into::into).collect();
+        let clip = clip.map(Into::into);
+
+        let mut raw = MaybeUninit::uninit();
+        let ret = unsafe {
+            bind::SDL_EncloseFPoints(
+                points.as_ptr(),
+                points.len() as c_int,
+                as_raw(&clip),
+                raw.as_mut_ptr(),
+            )
+        };
+        (ret != 0).then(|| unsafe { raw.assume_init() }.into())
+    }
+
+    /// Returns whether two rectangles intersected.
+    #[must_use]
+    pub fn has_intersection(self, other: Self) -> bool {
+        unsafe { bind::SDL_HasIntersectionF(&self.into(), &other.into()) != 0 }
+    }
+
+    /// Returns the intersection rectangle of two rectangles.
+    #[must_use]
+    pub fn intersect(self, other: Self) -> Option<Self> {
+        let mut raw = MaybeUninit::uninit();
+        let ret =
+            unsafe { bind::SDL_IntersectFRect(&self.into(), &other.into(), raw.as_mut_ptr()) };
+        (ret != 0).then(|| unsafe { raw.assume_init() }.into())
+    }
+
+    /// Returns whether the rectangle is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// Returns the union of two rectangles.
+    pub fn union(self, other: Self) -> Self {
+        let mut raw = MaybeUninit::uninit();
+        unsafe { bind::SDL_UnionFRect(&self.into(), &other.into(), raw.as_mut_ptr()) }
+        unsafe { raw.assume_init() }.into()
+    }
+}
+
+/// A straight line from point to point in floating-point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+#[must_use]
+pub struct FLine {
+    /// A start point of the line.
+    pub start: FPoint,
+    /// A end point of the line.
+    pub end: FPoint,
+}
+
+impl From<Line> for FLine {
+    fn from(Line { start, end }: Line) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+}
+
+impl FLine {
+    /// Calculates the new line moved with the offset.
+    pub fn offset(self, x: f32, y: f32) -> Self {
+        Self {
+            start: self.start.offset(x, y),
+            end: self.end.offset(x, y),
+        }
+    }
+
+    /// Returns the length of the line.
+    #[must_use]
+    pub fn length(self) -> f32 {
+        (self.end - self.start).length()
+    }
+
+    /// Clips the line with a rect.
+    pub fn clip_with(mut self, rect: FRect) -> Self {
+        unsafe {
+            bind::SDL_IntersectFRectAndLine(
+                &(rect.into()),
+                &mut self.start.x,
+                &mut self.start.y,
+                &mut self.end.x,
+                &mut self.end.y,
+            );
+        }
+        self
+    }
+}
@@ -1,6 +1,8 @@
-//! Renderer for a window, to render some geometries or query driver information.
+//! Renderer for a window or a surface, to render some geometries or query driver information.
 
 use static_assertions::assert_not_impl_all;
+use std::cell::Cell;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 
@@ -9,29 +11,50 @@ use clip::ClippedRenderer;
 use super::window::Window;
 use crate::{
     as_raw, bind,
-    geo::{Rect, Scale, Size},
+    color::{pixel::kind::PixelFormatKind, Rgb},
+    geo::{Point, Rect, Scale, Size},
+    surface::{owned::Owned, RawSurface, Surface},
     texture::Texture,
-    Result, Sdl, SdlError,
+    EnumInt, Result, Sdl, SdlError,
 };
 
+pub mod batch;
+mod builder;
+pub mod camera;
 pub mod clip;
 pub mod driver;
+mod frame;
+pub mod geometry;
 pub mod info;
+pub mod nine_slice;
 mod paste;
 pub mod pen;
+pub mod shape;
+pub mod target;
 
+pub use builder::*;
+pub use frame::*;
 pub use paste::*;
 
-/// A SDL2 renderer. This is often used for rendering with [`pen::Pen`].
+/// An output of the renderer.
+#[derive(Debug)]
+enum RenderOutput<'window> {
+    Window(&'window Window<'window>),
+    Surface(NonNull<RawSurface>, PhantomData<&'window mut ()>),
+}
+
+/// A SDL2 renderer. This is often used for rendering with [`Frame`], [`pen::Pen`] and [`Paster`].
 pub struct Renderer<'window> {
     renderer: NonNull<bind::SDL_Renderer>,
-    window: &'window Window<'window>,
+    output: RenderOutput<'window>,
+    transform: Cell<camera::Transform>,
 }
 
 impl std::fmt::Debug for Renderer<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Renderer")
-            .field("window", &self.window)
+            .field("output", &self.output)
+            .field("transform", &self.transform.get())
             .finish_non_exhaustive()
     }
 }
@@ -39,7 +62,7 @@ impl std::fmt::Debug for Renderer<'_> {
 assert_not_impl_all!(Renderer: Send, Sync);
 
 impl<'window> Renderer<'window> {
-    /// Constructs a renderer from the window.
+    /// Constructs a software renderer from the window. Use [`RendererBuilder`] to select the driver and flags.
     #[must_use]
     pub fn new(window: &'window Window) -> Self {
         let raw = unsafe {
@@ -47,7 +70,31 @@ impl<'window> Renderer<'window> {
         };
         NonNull::new(raw).map_or_else(
             || Sdl::error_then_panic("Sdl renderer"),
-            |renderer| Self { renderer, window },
+            |renderer| Self {
+                renderer,
+                output: RenderOutput::Window(window),
+                transform: Cell::default(),
+            },
+        )
+    }
+
+    /// Constructs a software renderer drawing into the surface, without any window. The rendered pixels can be read from the surface after the renderer is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to create the renderer for the surface.
+    pub fn from_surface<S: Surface>(surface: &'window mut S) -> Result<Self> {
+        let surface = surface.as_ptr();
+        let raw = unsafe { bind::SDL_CreateSoftwareRenderer(surface.as_ptr()) };
+        NonNull::new(raw).map_or_else(
+            || Err(SdlError::Others { msg: Sdl::error() }),
+            |renderer| {
+                Ok(Self {
+                    renderer,
+                    output: RenderOutput::Surface(surface, PhantomData),
+                    transform: Cell::default(),
+                })
+            },
         )
     }
 
@@ -56,9 +103,39 @@ impl<'window> Renderer<'window> {
     }
 
     /// Returns the borrowing window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the renderer draws into a surface, see [`Renderer::output_window`] for such renderers.
     #[must_use]
     pub fn window(&self) -> &Window {
-        self.window
+        self.output_window()
+            .expect("the renderer must draw into a window")
+    }
+
+    /// Returns the borrowing window, or `None` if the renderer draws into a surface.
+    #[must_use]
+    pub fn output_window(&self) -> Option<&Window> {
+        match self.output {
+            RenderOutput::Window(window) => Some(window),
+            RenderOutput::Surface(..) => None,
+        }
+    }
+
+    /// Returns the pixel format of the output.
+    pub(crate) fn output_format(&self) -> PixelFormatKind {
+        match self.output {
+            RenderOutput::Window(window) => window.pixel_format(),
+            RenderOutput::Surface(surface, _) => {
+                PixelFormatKind::from_raw(unsafe { (*surface.as_ref().format).format } as EnumInt)
+            }
+        }
+    }
+
+    /// Starts a new [`Frame`] to render, clearing all the area with `background` if exists. The frame will be presented on dropped.
+    #[must_use]
+    pub fn frame(&self, background: Option<Rgb>) -> Frame {
+        Frame::new(self, background)
     }
 
     /// Returns the geometry size of the output from the renderer.
@@ -77,6 +154,20 @@ impl<'window> Renderer<'window> {
         }
     }
 
+    /// Sets whether the present is synchronized with the refresh rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if changing vertical sync is unsupported by the driver.
+    pub fn set_v_sync(&self, enabled: bool) -> Result<()> {
+        let ret = unsafe { bind::SDL_RenderSetVSync(self.as_ptr(), if enabled { 1 } else { 0 }) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
     /// Clips the renderer by `area`.
     pub fn clip(&'window mut self, area: Rect) -> ClippedRenderer<'window> {
         ClippedRenderer::new(self, area)
@@ -164,7 +255,54 @@ impl<'window> Renderer<'window> {
         }
     }
 
-    /// Sets the render target to the texture.
+    /// Reads the pixels in `area` of the current render target in its pixels not scaled, or the whole viewport if `None`, into a new surface with the format `kind`.
+    ///
+    /// This is slow, so you should not use it on every frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface, or the driver does not support reading pixels.
+    pub fn read_pixels(&self, area: Option<Rect>, kind: PixelFormatKind) -> Result<Owned> {
+        // SDL2 reads at most the given rect, so pass always the rect in the size of the surface.
+        let area = area.unwrap_or_else(|| self.physical_viewport());
+        let surface = Owned::with_kind(area.size, kind)?;
+        let raw_area = area.into();
+        let ret = unsafe {
+            let raw_surface = surface.as_ptr().as_ref();
+            bind::SDL_RenderReadPixels(
+                self.as_ptr(),
+                &raw_area,
+                kind.as_raw(),
+                raw_surface.pixels,
+                raw_surface.pitch,
+            )
+        };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+        Ok(surface)
+    }
+
+    /// Returns the viewport in the pixels of the render target, not divided by the scale.
+    fn physical_viewport(&self) -> Rect {
+        let viewport = self.viewport();
+        let Scale {
+            horizontal,
+            vertical,
+        } = self.scale();
+        Rect {
+            up_left: Point {
+                x: (viewport.up_left.x as f32 * horizontal).floor() as i32,
+                y: (viewport.up_left.y as f32 * vertical).floor() as i32,
+            },
+            size: Size {
+                width: (viewport.size.width as f32 * horizontal).round() as u32,
+                height: (viewport.size.height as f32 * vertical).round() as u32,
+            },
+        }
+    }
+
+    /// Sets the render target to the texture. [`Renderer::with_target`] is recommended instead, which checks the texture and restores the previous target.
     pub fn set_target<'texture: 'window>(&'window self, texture: &'texture Texture) {
         let ret = unsafe { bind::SDL_SetRenderTarget(self.as_ptr(), texture.as_ptr()) };
         if ret != 0 {
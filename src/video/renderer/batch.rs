@@ -0,0 +1,270 @@
+//! Batching many sprites into a few geometry renderings.
+
+use typed_builder::TypedBuilder;
+
+use crate::color::Rgba;
+use crate::geo::{FPoint, FRect, Rect};
+use crate::texture::{QueryExt, Texture};
+use crate::{bind, Result};
+
+use super::geometry::{Vertex, VertexIndices};
+use super::{PasteExFlip, Renderer};
+
+/// A sprite to draw with [`SpriteBatch`], the same as the options of [`Paster::paste_ex_f`](super::Paster::paste_ex_f).
+#[derive(Debug, Clone, Copy, TypedBuilder)]
+pub struct Sprite<'texture> {
+    /// The texture to paste from.
+    pub texture: &'texture Texture<'texture>,
+    /// The source area in the texture, or the clip of the texture if `None`.
+    #[builder(default, setter(strip_option))]
+    pub source_area: Option<Rect>,
+    /// The target area to paste into.
+    #[builder(setter(into))]
+    pub target_area: FRect,
+    /// The degrees of rotating the sprite clockwise.
+    #[builder(default)]
+    pub rotation_degrees: f32,
+    /// The center point of rotation relative to the up left of the target area, or the center of it if `None`.
+    #[builder(default, setter(strip_option))]
+    pub center: Option<FPoint>,
+    /// The flip mode of the sprite.
+    #[builder(default)]
+    pub flip: PasteExFlip,
+    /// The color multiplied into the texture color, works as the color and alpha modulation.
+    #[builder(default = Rgba { r: 255, g: 255, b: 255, a: 255 })]
+    pub color: Rgba,
+}
+
+#[derive(Debug)]
+struct BatchEntry<'texture> {
+    sprite: Sprite<'texture>,
+    blend_mode: bind::SDL_BlendMode,
+}
+
+/// A batch of sprites, renders them with a few calls of geometry rendering instead of pasting them one by one.
+///
+/// The sprites are sorted by the blend mode and the texture on flushing, so the sprites with different textures may be drawn in a different order from pushed. Disable sorting with [`SpriteBatch::sorts`] if the order matters.
+///
+/// The sprites are drawn with the current transform of the renderer, see [`Renderer::push_transform`]. Note that the color and alpha modulation of the texture are not applied, use [`Sprite::color`] instead.
+#[derive(Debug)]
+pub struct SpriteBatch<'renderer, 'texture> {
+    renderer: &'renderer Renderer<'renderer>,
+    capacity: usize,
+    sorts: bool,
+    entries: Vec<BatchEntry<'texture>>,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl<'renderer, 'texture> SpriteBatch<'renderer, 'texture> {
+    /// Constructs a batch on the renderer, flushing automatically when `capacity` sprites are pushed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn new(renderer: &'renderer Renderer<'renderer>, capacity: usize) -> Self {
+        assert!(0 < capacity, "capacity must be positive");
+        Self {
+            renderer,
+            capacity,
+            sorts: true,
+            entries: Vec::with_capacity(capacity),
+            vertices: Vec::with_capacity(capacity * 4),
+            indices: Vec::with_capacity(capacity * 6),
+        }
+    }
+
+    /// Sets whether the sprites are sorted by the blend mode and the texture on flushing. This is enabled by default.
+    #[must_use]
+    pub fn sorts(mut self, sorts: bool) -> Self {
+        self.sorts = sorts;
+        self
+    }
+
+    /// Returns the number of sprites that flushes the batch automatically.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of pending sprites.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no pending sprites.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pushes the sprite into the batch, and flushes if the batch reached the capacity.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to flush the batch.
+    pub fn push(&mut self, sprite: Sprite<'texture>) -> Result<()> {
+        let blend_mode = sprite.texture.blend_mode().into();
+        self.entries.push(BatchEntry { sprite, blend_mode });
+        if self.capacity <= self.entries.len() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Renders all the pending sprites, grouped by the texture and the blend mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to render the geometry.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn flush(&mut self) -> Result<()> {
+        if self.sorts {
+            self.entries.sort_by_key(|entry| {
+                (
+                    entry.blend_mode as u32,
+                    entry.sprite.texture.as_ptr() as usize,
+                )
+            });
+        }
+        let mut entries = std::mem::take(&mut self.entries);
+        let result =
+            ChunksByTexture { rest: &entries }.try_for_each(|group| self.render_group(group));
+        entries.clear();
+        self.entries = entries;
+        result
+    }
+
+    fn render_group(&mut self, group: &[BatchEntry]) -> Result<()> {
+        let texture = group[0].sprite.texture;
+        let size = texture.size();
+        let texture_size = FPoint {
+            x: size.width as f32,
+            y: size.height as f32,
+        };
+        let transform = self.renderer.transform();
+        self.vertices.clear();
+        self.indices.clear();
+        for entry in group {
+            let first = self.vertices.len() as u32;
+            let mut vertices = sprite_vertices(&entry.sprite, texture_size);
+            for vertex in &mut vertices {
+                vertex.position = transform.apply(vertex.position);
+            }
+            self.vertices.extend_from_slice(&vertices);
+            self.indices.extend_from_slice(&[
+                first,
+                first + 1,
+                first + 2,
+                first + 2,
+                first + 3,
+                first,
+            ]);
+        }
+        self.renderer.render_geometry(
+            Some(texture),
+            &self.vertices,
+            Some(VertexIndices::U32(&self.indices)),
+        )
+    }
+}
+
+impl Drop for SpriteBatch<'_, '_> {
+    fn drop(&mut self) {
+        // Flushes the pending sprites, ignoring the error because cannot report it here.
+        let _ = self.flush();
+    }
+}
+
+/// An iterator of consecutive entries having the same texture and blend mode.
+struct ChunksByTexture<'a, 'texture> {
+    rest: &'a [BatchEntry<'texture>],
+}
+
+impl<'a, 'texture> Iterator for ChunksByTexture<'a, 'texture> {
+    type Item = &'a [BatchEntry<'texture>];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.rest.first()?;
+        let len = self
+            .rest
+            .iter()
+            .take_while(|entry| {
+                entry.blend_mode == first.blend_mode
+                    && entry.sprite.texture.as_ptr() == first.sprite.texture.as_ptr()
+            })
+            .count();
+        let (chunk, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(chunk)
+    }
+}
+
+/// Calculates four vertices of the sprite in order of up left, up right, down right and down left.
+fn sprite_vertices(sprite: &Sprite, texture_size: FPoint) -> [Vertex; 4] {
+    let FRect {
+        up_left,
+        width,
+        height,
+    } = sprite.target_area;
+    let source = sprite.source_area.or(*sprite.texture.clip()).map_or(
+        FRect {
+            up_left: FPoint { x: 0.0, y: 0.0 },
+            width: texture_size.x,
+            height: texture_size.y,
+        },
+        Into::into,
+    );
+    let (mut left, mut right) = (
+        source.up_left.x / texture_size.x,
+        (source.up_left.x + source.width) / texture_size.x,
+    );
+    let (mut top, mut bottom) = (
+        source.up_left.y / texture_size.y,
+        (source.up_left.y + source.height) / texture_size.y,
+    );
+    if sprite.flip.contains(PasteExFlip::HORIZONTAL) {
+        std::mem::swap(&mut left, &mut right);
+    }
+    if sprite.flip.contains(PasteExFlip::VERTICAL) {
+        std::mem::swap(&mut top, &mut bottom);
+    }
+
+    let center = sprite.center.unwrap_or(FPoint {
+        x: width / 2.0,
+        y: height / 2.0,
+    });
+    let (sin, cos) = sprite.rotation_degrees.to_radians().sin_cos();
+    let transform = |x: f32, y: f32| {
+        let (dx, dy) = (x - center.x, y - center.y);
+        FPoint {
+            x: up_left.x + center.x + dx * cos - dy * sin,
+            y: up_left.y + center.y + dx * sin + dy * cos,
+        }
+    };
+    let vertex = |position: FPoint, tex_x: f32, tex_y: f32| Vertex {
+        position,
+        color: sprite.color,
+        tex_coord: FPoint { x: tex_x, y: tex_y },
+    };
+    [
+        vertex(transform(0.0, 0.0), left, top),
+        vertex(transform(width, 0.0), right, top),
+        vertex(transform(width, height), right, bottom),
+        vertex(transform(0.0, height), left, bottom),
+    ]
+}
+
+impl Renderer<'_> {
+    /// Constructs the [`SpriteBatch`] on the renderer, flushing automatically when `capacity` sprites are pushed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn sprite_batch<'texture>(&self, capacity: usize) -> SpriteBatch<'_, 'texture> {
+        SpriteBatch::new(self, capacity)
+    }
+}
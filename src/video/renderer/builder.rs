@@ -0,0 +1,145 @@
+use bitflags::bitflags;
+use std::cell::Cell;
+use std::ptr::NonNull;
+use typed_builder::TypedBuilder;
+
+use super::{driver, RenderOutput, Renderer};
+use crate::{bind, window::Window, Result, Sdl, SdlError};
+
+/// A selection of the render driver for [`RendererBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriverSelection {
+    /// Uses the first driver supporting the requested flags.
+    FirstSupported,
+    /// Uses the driver at the index in [`driver::drivers`].
+    Index(usize),
+    /// Uses the driver of the name, such as `"software"`, `"opengl"` or `"direct3d"`.
+    Name(String),
+}
+
+impl Default for DriverSelection {
+    fn default() -> Self {
+        Self::FirstSupported
+    }
+}
+
+impl From<usize> for DriverSelection {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for DriverSelection {
+    fn from(name: &str) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+/// A builder for the [`Renderer`].
+///
+/// Building tries these steps in order, and returns the first renderer successfully created:
+///
+/// 1. The selected driver with the requested flags.
+/// 2. If a specific driver was selected, the first driver supporting the requested flags.
+/// 3. If `software_fallback` is enabled, the software renderer without acceleration and vertical sync.
+#[derive(Debug, TypedBuilder)]
+pub struct RendererBuilder {
+    #[builder(default, setter(into))]
+    driver: DriverSelection,
+    #[builder(default)]
+    accelerated: bool,
+    #[builder(default)]
+    v_sync: bool,
+    #[builder(default)]
+    target_texture: bool,
+    #[builder(default = true)]
+    software_fallback: bool,
+}
+
+impl RendererBuilder {
+    /// Builds the renderer for the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no renderer could be created in the fallback order. A selected driver which does not exist is skipped as failed.
+    pub fn new_renderer<'window>(self, window: &'window Window) -> Result<Renderer<'window>> {
+        let flags = self.calc_flags();
+
+        // A selected driver which does not exist fails only the first step, and the rest are tried.
+        let mut last_error = None;
+        let mut candidates = vec![];
+        match &self.driver {
+            DriverSelection::FirstSupported => {}
+            DriverSelection::Index(index) if *index < driver::drivers().len() => {
+                candidates.push((Some(*index), flags));
+            }
+            DriverSelection::Index(index) => {
+                last_error = Some(format!("render driver index {} out of range", index));
+            }
+            DriverSelection::Name(name) => match driver::index_of(name) {
+                Some(index) => candidates.push((Some(index), flags)),
+                None => last_error = Some(format!("render driver `{}` not found", name)),
+            },
+        }
+        candidates.push((None, flags));
+        if self.software_fallback {
+            let mut software_flags = RendererFlags::SOFTWARE;
+            if self.target_texture {
+                software_flags |= RendererFlags::TARGET_TEXTURE;
+            }
+            candidates.push((None, software_flags));
+        }
+
+        for (index, flags) in candidates {
+            let raw = unsafe {
+                bind::SDL_CreateRenderer(
+                    window.as_ptr(),
+                    index.map_or(-1, |index| index as i32),
+                    flags.bits(),
+                )
+            };
+            match NonNull::new(raw) {
+                Some(renderer) => {
+                    return Ok(Renderer {
+                        renderer,
+                        output: RenderOutput::Window(window),
+                        transform: Cell::default(),
+                    })
+                }
+                None => last_error = Some(Sdl::error()),
+            }
+        }
+        Err(SdlError::Others {
+            msg: last_error.unwrap_or_default(),
+        })
+    }
+
+    fn calc_flags(&self) -> RendererFlags {
+        let mut flags = RendererFlags::empty();
+        if self.accelerated {
+            flags |= RendererFlags::ACCELERATED;
+        }
+        if self.v_sync {
+            flags |= RendererFlags::PRESENT_V_SYNC;
+        }
+        if self.target_texture {
+            flags |= RendererFlags::TARGET_TEXTURE;
+        }
+        flags
+    }
+}
+
+bitflags! {
+    /// A flag for [`Renderer`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub(super) struct RendererFlags: u32 {
+        #[allow(clippy::unnecessary_cast)]
+        const SOFTWARE = bind::SDL_RENDERER_SOFTWARE as u32;
+        #[allow(clippy::unnecessary_cast)]
+        const ACCELERATED = bind::SDL_RENDERER_ACCELERATED as u32;
+        #[allow(clippy::unnecessary_cast)]
+        const PRESENT_V_SYNC = bind::SDL_RENDERER_PRESENTVSYNC as u32;
+        #[allow(clippy::unnecessary_cast)]
+        const TARGET_TEXTURE = bind::SDL_RENDERER_TARGETTEXTURE as u32;
+    }
+}
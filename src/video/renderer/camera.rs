@@ -0,0 +1,320 @@
+//! Transforming the drawing coordinates with a camera or an affine transform.
+
+use crate::bind;
+use crate::geo::{FPoint, FRect, Point};
+
+use super::{pen::Pen, Paster, Renderer};
+
+/// An affine transform of 2D coordinates, maps `(x, y)` into `(a * x + c * y + tx, b * x + d * y + ty)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// The factor of x into new x.
+    pub a: f32,
+    /// The factor of x into new y.
+    pub b: f32,
+    /// The factor of y into new x.
+    pub c: f32,
+    /// The factor of y into new y.
+    pub d: f32,
+    /// The translation of new x.
+    pub tx: f32,
+    /// The translation of new y.
+    pub ty: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The transform that does nothing.
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    /// Constructs the transform moving by `x` and `y`.
+    #[must_use]
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            tx: x,
+            ty: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Constructs the transform scaling around the origin.
+    #[must_use]
+    pub fn scaling(horizontal: f32, vertical: f32) -> Self {
+        Self {
+            a: horizontal,
+            d: vertical,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Constructs the transform rotating clockwise on the screen around the origin.
+    #[must_use]
+    pub fn rotation(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Constructs the transform rotating clockwise on the screen around `pivot`.
+    #[must_use]
+    pub fn rotation_about(pivot: FPoint, degrees: f32) -> Self {
+        Self::translation(-pivot.x, -pivot.y)
+            .then(Self::rotation(degrees))
+            .then(Self::translation(pivot.x, pivot.y))
+    }
+
+    /// Composes the transform applying `self` and then `next`.
+    #[must_use]
+    pub fn then(self, next: Self) -> Self {
+        Self {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            tx: next.a * self.tx + next.c * self.ty + next.tx,
+            ty: next.b * self.tx + next.d * self.ty + next.ty,
+        }
+    }
+
+    /// Returns the inverse transform, or `None` if the transform is not invertible.
+    #[must_use]
+    pub fn inverse(self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+
+    /// Returns whether the transform does nothing.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns whether the transform keeps the rectangles aligned to the axes, that is no rotation.
+    #[must_use]
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0
+    }
+
+    /// Applies the transform to the point.
+    pub fn apply(&self, FPoint { x, y }: FPoint) -> FPoint {
+        FPoint {
+            x: self.a * x + self.c * y + self.tx,
+            y: self.b * x + self.d * y + self.ty,
+        }
+    }
+
+    /// Applies the transform to the rectangle, returning the bounding rectangle of the transformed corners.
+    pub fn apply_rect(&self, rect: FRect) -> FRect {
+        let corners = self.corners(rect);
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min.x = min.x.min(corner.x);
+            min.y = min.y.min(corner.y);
+            max.x = max.x.max(corner.x);
+            max.y = max.y.max(corner.y);
+        }
+        FRect {
+            up_left: min,
+            width: max.x - min.x,
+            height: max.y - min.y,
+        }
+    }
+
+    /// Returns the transformed corners of the rectangle, in order of up left, up right, down right and down left.
+    pub fn corners(&self, rect: FRect) -> [FPoint; 4] {
+        let FRect {
+            up_left,
+            width,
+            height,
+        } = rect;
+        [
+            self.apply(up_left),
+            self.apply(up_left.offset(width, 0.0)),
+            self.apply(up_left.offset(width, height)),
+            self.apply(up_left.offset(0.0, height)),
+        ]
+    }
+
+    /// Decomposes the transform into the horizontal scale, the vertical scale and the clockwise rotation degrees. The vertical scale is negative if the transform flips.
+    #[must_use]
+    pub fn decompose(&self) -> (f32, f32, f32) {
+        let horizontal = self.a.hypot(self.b);
+        let det = self.a * self.d - self.b * self.c;
+        let vertical = if horizontal == 0.0 {
+            self.c.hypot(self.d)
+        } else {
+            det / horizontal
+        };
+        (horizontal, vertical, self.b.atan2(self.a).to_degrees())
+    }
+}
+
+/// A 2D camera viewing the world, makes the transform from the world coordinates into the render coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    /// The position in the world to look at.
+    pub position: FPoint,
+    /// The zoom factor, larger shows the world bigger.
+    pub zoom: f32,
+    /// The degrees of rotating the camera clockwise, so the world looks rotated counter-clockwise.
+    pub rotation_degrees: f32,
+    /// The point in the render coordinates where [`Camera2D::position`] appears, such as the center of the logical size.
+    pub pivot: FPoint,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self {
+            position: FPoint::default(),
+            zoom: 1.0,
+            rotation_degrees: 0.0,
+            pivot: FPoint::default(),
+        }
+    }
+}
+
+impl Camera2D {
+    /// Constructs the camera looking at `position`, showing it at `pivot` in the render coordinates.
+    pub fn new(position: FPoint, pivot: FPoint) -> Self {
+        Self {
+            position,
+            pivot,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the transform from the world coordinates into the render coordinates.
+    #[must_use]
+    pub fn transform(&self) -> Transform {
+        Transform::translation(-self.position.x, -self.position.y)
+            .then(Transform::scaling(self.zoom, self.zoom))
+            .then(Transform::rotation(-self.rotation_degrees))
+            .then(Transform::translation(self.pivot.x, self.pivot.y))
+    }
+
+    /// Converts the world coordinates into the window coordinates, with the logical size, scale and viewport of the renderer.
+    #[must_use]
+    pub fn world_to_screen(&self, renderer: &Renderer, world: FPoint) -> Point {
+        renderer.logical_to_window(self.transform().apply(world))
+    }
+
+    /// Converts the window coordinates into the world coordinates, with the logical size, scale and viewport of the renderer. This is useful for picking by the mouse position.
+    ///
+    /// Returns `None` if the transform is not invertible, such as the zoom is zero.
+    #[must_use]
+    pub fn screen_to_world(&self, renderer: &Renderer, screen: Point) -> Option<FPoint> {
+        let logical = renderer.window_to_logical(screen);
+        self.transform()
+            .inverse()
+            .map(|inverse| inverse.apply(logical))
+    }
+}
+
+/// A guard applying the transform to [`Pen`] and [`Paster`] of the renderer. The previous transform is restored on dropped.
+///
+/// Guards can be nested, then the inner transform is applied before the outer one.
+#[derive(Debug)]
+pub struct TransformGuard<'renderer> {
+    renderer: &'renderer Renderer<'renderer>,
+    prev: Transform,
+}
+
+impl<'renderer> TransformGuard<'renderer> {
+    /// Pushes `transform` onto the current transform of `renderer`.
+    #[must_use]
+    pub fn new(renderer: &'renderer Renderer, transform: Transform) -> Self {
+        let prev = renderer.transform();
+        renderer.transform.set(transform.then(prev));
+        Self { renderer, prev }
+    }
+
+    /// Returns the renderer whose transform is pushed.
+    #[must_use]
+    pub fn renderer(&self) -> &Renderer {
+        self.renderer
+    }
+
+    /// Returns the pen to draw geometries with the transform.
+    #[must_use]
+    pub fn pen(&self) -> Pen {
+        Pen::view(self.renderer)
+    }
+
+    /// Returns the paster to paste textures with the transform.
+    #[must_use]
+    pub fn paster(&self) -> Paster {
+        Paster::view(self.renderer)
+    }
+}
+
+impl Drop for TransformGuard<'_> {
+    fn drop(&mut self) {
+        self.renderer.transform.set(self.prev);
+    }
+}
+
+impl Renderer<'_> {
+    /// Returns the current transform applied to [`Pen`] and [`Paster`].
+    #[must_use]
+    pub fn transform(&self) -> Transform {
+        self.transform.get()
+    }
+
+    /// Pushes `transform` onto the current transform until the returned guard is dropped.
+    #[must_use]
+    pub fn push_transform(&self, transform: Transform) -> TransformGuard {
+        TransformGuard::new(self, transform)
+    }
+
+    /// Pushes the transform of `camera`, and calls `draw` with the guard. The previous transform is restored after that.
+    pub fn with_camera<R>(&self, camera: &Camera2D, draw: impl FnOnce(&TransformGuard) -> R) -> R {
+        let guard = TransformGuard::new(self, camera.transform());
+        draw(&guard)
+    }
+
+    /// Converts the window coordinates into the render coordinates, with the logical size, scale and viewport.
+    pub fn window_to_logical(&self, Point { x, y }: Point) -> FPoint {
+        let mut logical = FPoint::default();
+        unsafe {
+            bind::SDL_RenderWindowToLogical(self.as_ptr(), x, y, &mut logical.x, &mut logical.y);
+        }
+        logical
+    }
+
+    /// Converts the render coordinates into the window coordinates, with the logical size, scale and viewport.
+    pub fn logical_to_window(&self, FPoint { x, y }: FPoint) -> Point {
+        let mut window = Point::default();
+        unsafe {
+            bind::SDL_RenderLogicalToWindow(self.as_ptr(), x, y, &mut window.x, &mut window.y);
+        }
+        window
+    }
+}
@@ -20,3 +20,9 @@ pub fn drivers() -> Vec<RendererInfo> {
         })
         .collect()
 }
+
+/// Returns the index of the render driver named `name` in [`drivers`], or `None` if not found.
+#[must_use]
+pub fn index_of(name: &str) -> Option<usize> {
+    drivers().iter().position(|info| info.name == name)
+}
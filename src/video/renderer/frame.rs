@@ -0,0 +1,67 @@
+use crate::color::Rgb;
+use crate::{bind, Sdl};
+
+use super::{pen::Pen, Paster, Renderer};
+
+/// A frame of rendering, clears the renderer on start and presents on dropped.
+///
+/// [`Pen`] and [`Paster`] obtained from the frame are views to draw into it, so you can mix shapes and textures in one frame. A frame given by [`Renderer::with_target`] draws into the texture and does not present.
+#[derive(Debug)]
+pub struct Frame<'renderer> {
+    renderer: &'renderer Renderer<'renderer>,
+    presents: bool,
+}
+
+impl<'renderer> Frame<'renderer> {
+    /// Starts the frame on the renderer, clearing all the area with `background` if exists.
+    #[must_use]
+    pub fn new(renderer: &'renderer Renderer, background: Option<Rgb>) -> Self {
+        if let Some(Rgb { r, g, b }) = background {
+            let ret = unsafe { bind::SDL_SetRenderDrawColor(renderer.as_ptr(), r, g, b, 255) };
+            if ret != 0 {
+                Sdl::error_then_panic("Setting frame background color");
+            }
+            let ret = unsafe { bind::SDL_RenderClear(renderer.as_ptr()) };
+            if ret != 0 {
+                Sdl::error_then_panic("Clearing frame");
+            }
+        }
+        Self {
+            renderer,
+            presents: true,
+        }
+    }
+
+    pub(super) fn on_target(renderer: &'renderer Renderer) -> Self {
+        Self {
+            renderer,
+            presents: false,
+        }
+    }
+
+    /// Returns the renderer that the frame is drawing.
+    #[must_use]
+    pub fn renderer(&self) -> &Renderer {
+        self.renderer
+    }
+
+    /// Returns the pen to draw geometries into the frame.
+    #[must_use]
+    pub fn pen(&self) -> Pen {
+        Pen::view(self.renderer)
+    }
+
+    /// Returns the paster to paste textures into the frame.
+    #[must_use]
+    pub fn paster(&self) -> Paster {
+        Paster::view(self.renderer)
+    }
+}
+
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        if self.presents {
+            unsafe { bind::SDL_RenderPresent(self.renderer.as_ptr()) }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Rendering arbitrary triangle meshes with the vertices.
+
+use std::os::raw::c_int;
+use std::ptr::addr_of;
+
+use crate::color::Rgba;
+use crate::geo::FPoint;
+use crate::texture::Texture;
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::Renderer;
+
+/// A vertex of the triangle mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex in the renderer.
+    pub position: FPoint,
+    /// The color of the vertex, multiplied into the texture color if exists.
+    pub color: Rgba,
+    /// The normalized texture coordinate of the vertex, ignored if there is no texture.
+    pub tex_coord: FPoint,
+}
+
+impl From<Vertex> for bind::SDL_Vertex {
+    fn from(
+        Vertex {
+            position,
+            color,
+            tex_coord,
+        }: Vertex,
+    ) -> Self {
+        Self {
+            position: position.into(),
+            color: color.into(),
+            tex_coord: tex_coord.into(),
+        }
+    }
+}
+
+/// Indices to the vertices, every three indices make a triangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexIndices<'a> {
+    /// Indices in 16-bit integers.
+    U16(&'a [u16]),
+    /// Indices in 32-bit integers.
+    U32(&'a [u32]),
+}
+
+impl Renderer<'_> {
+    /// Renders the triangles made from `vertices` with the texture if exists. If `indices` is `None`, every three vertices make a triangle in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if an index is out of `vertices`, or failed to render the geometry.
+    pub fn render_geometry(
+        &self,
+        texture: Option<&Texture>,
+        vertices: &[Vertex],
+        indices: Option<VertexIndices>,
+    ) -> Result<()> {
+        if vertices.is_empty() {
+            return Ok(());
+        }
+        let in_range = match indices {
+            None => true,
+            Some(VertexIndices::U16(indices)) => indices
+                .iter()
+                .all(|&index| usize::from(index) < vertices.len()),
+            Some(VertexIndices::U32(indices)) => indices
+                .iter()
+                .all(|&index| (index as usize) < vertices.len()),
+        };
+        if !in_range {
+            return Err(SdlError::Others {
+                msg: "index out of the vertices".into(),
+            });
+        }
+        let raw_vertices: Vec<bind::SDL_Vertex> =
+            vertices.iter().copied().map(Into::into).collect();
+        let texture = texture.map_or(std::ptr::null_mut(), Texture::as_ptr);
+        let ret = match indices {
+            None => unsafe {
+                bind::SDL_RenderGeometry(
+                    self.as_ptr(),
+                    texture,
+                    raw_vertices.as_ptr(),
+                    raw_vertices.len() as c_int,
+                    std::ptr::null(),
+                    0,
+                )
+            },
+            Some(VertexIndices::U32(indices)) => unsafe {
+                bind::SDL_RenderGeometry(
+                    self.as_ptr(),
+                    texture,
+                    raw_vertices.as_ptr(),
+                    raw_vertices.len() as c_int,
+                    indices.as_ptr().cast(),
+                    indices.len() as c_int,
+                )
+            },
+            Some(VertexIndices::U16(indices)) => {
+                let stride = std::mem::size_of::<bind::SDL_Vertex>() as c_int;
+                let first = raw_vertices.as_ptr();
+                unsafe {
+                    bind::SDL_RenderGeometryRaw(
+                        self.as_ptr(),
+                        texture,
+                        addr_of!((*first).position.x),
+                        stride,
+                        addr_of!((*first).color),
+                        stride,
+                        addr_of!((*first).tex_coord.x),
+                        stride,
+                        raw_vertices.len() as c_int,
+                        indices.as_ptr().cast(),
+                        indices.len() as c_int,
+                        std::mem::size_of::<u16>() as c_int,
+                    )
+                }
+            }
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! Pasting a texture as nine slices, keeping the corners intact.
+
+use typed_builder::TypedBuilder;
+
+use crate::geo::{FPoint, FRect, Point, Rect, Size};
+use crate::texture::{QueryExt, Texture};
+
+use super::Paster;
+
+/// Insets from the edges of the source area, to divide it into nine slices.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Insets {
+    /// The width of the left column.
+    pub left: u32,
+    /// The height of the top row.
+    pub top: u32,
+    /// The width of the right column.
+    pub right: u32,
+    /// The height of the bottom row.
+    pub bottom: u32,
+}
+
+impl Insets {
+    /// Constructs the insets with the same length on all the edges.
+    #[must_use]
+    pub fn uniform(inset: u32) -> Self {
+        Self {
+            left: inset,
+            top: inset,
+            right: inset,
+            bottom: inset,
+        }
+    }
+}
+
+/// A way to fill the edges or the center of [`NineSlice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SliceFill {
+    /// Stretches the slice to fit.
+    Stretch,
+    /// Repeats the slice in the original size, the last one is cropped.
+    Tile,
+}
+
+impl Default for SliceFill {
+    fn default() -> Self {
+        Self::Stretch
+    }
+}
+
+/// A nine-slice description of the texture. The corners are pasted in the original size, and the edges and the center are stretched or tiled.
+///
+/// The source area is the clip of the texture, or the whole texture if no clip.
+#[derive(Debug, Clone, Copy, TypedBuilder)]
+pub struct NineSlice<'texture> {
+    /// The texture to paste from.
+    pub texture: &'texture Texture<'texture>,
+    /// The insets dividing the source area. The sum of the opposite insets must not exceed the source area.
+    pub insets: Insets,
+    /// The way to fill the edges.
+    #[builder(default)]
+    pub edges: SliceFill,
+    /// The way to fill the center.
+    #[builder(default)]
+    pub center: SliceFill,
+}
+
+/// A segment of a row or a column, mapping from the source into the target.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    source_start: i32,
+    source_len: u32,
+    target_start: f32,
+    target_len: f32,
+}
+
+impl Segment {
+    /// Divides the source and target range into the head, middle and tail segments. The head and tail are shrunk if the target is shorter than them.
+    fn divide(
+        (source_start, source_len): (i32, u32),
+        (head, tail): (u32, u32),
+        (target_start, target_len): (f32, f32),
+    ) -> [Self; 3] {
+        assert!(
+            head + tail <= source_len,
+            "insets must not exceed the source area"
+        );
+        let (mut target_head, mut target_tail) = (head as f32, tail as f32);
+        if target_len < target_head + target_tail {
+            let ratio = target_len / (target_head + target_tail);
+            target_head *= ratio;
+            target_tail *= ratio;
+        }
+        [
+            Self {
+                source_start,
+                source_len: head,
+                target_start,
+                target_len: target_head,
+            },
+            Self {
+                source_start: source_start + head as i32,
+                source_len: source_len - head - tail,
+                target_start: target_start + target_head,
+                target_len: target_len - target_head - target_tail,
+            },
+            Self {
+                source_start: source_start + (source_len - tail) as i32,
+                source_len: tail,
+                target_start: target_start + target_len - target_tail,
+                target_len: target_tail,
+            },
+        ]
+    }
+
+    /// Splits the segment into the tiles in the source length if `tiled`, otherwise returns itself.
+    fn tiles(self, tiled: bool) -> Vec<Self> {
+        if self.source_len == 0 || self.target_len <= 0.0 {
+            return vec![];
+        }
+        if !tiled {
+            return vec![self];
+        }
+        let mut tiles = vec![];
+        let mut offset = 0.0;
+        while offset < self.target_len {
+            let target_len = (self.target_len - offset).min(self.source_len as f32);
+            let source_len = (target_len.ceil() as u32).clamp(1, self.source_len);
+            tiles.push(Self {
+                source_start: self.source_start,
+                source_len,
+                target_start: self.target_start + offset,
+                target_len,
+            });
+            offset += self.source_len as f32;
+        }
+        tiles
+    }
+}
+
+impl Paster<'_> {
+    /// Pastes the nine slice into `target_area`. The color and alpha modulation of the texture are applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the insets exceed the source area.
+    pub fn paste_nine_slice(&self, slice: &NineSlice, target_area: Rect) {
+        self.paste_nine_slice_f(slice, target_area.into());
+    }
+
+    /// Pastes the nine slice into `target_area` with sub-pixel precision. The color and alpha modulation of the texture are applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the insets exceed the source area.
+    pub fn paste_nine_slice_f(&self, slice: &NineSlice, target_area: FRect) {
+        let source = slice.texture.clip().unwrap_or_else(|| Rect {
+            up_left: Point::default(),
+            size: slice.texture.size(),
+        });
+        let Insets {
+            left,
+            top,
+            right,
+            bottom,
+        } = slice.insets;
+        let columns = Segment::divide(
+            (source.up_left.x, source.size.width),
+            (left, right),
+            (target_area.up_left.x, target_area.width),
+        );
+        let rows = Segment::divide(
+            (source.up_left.y, source.size.height),
+            (top, bottom),
+            (target_area.up_left.y, target_area.height),
+        );
+        for (row_index, &row) in rows.iter().enumerate() {
+            for (column_index, &column) in columns.iter().enumerate() {
+                let fill = match (row_index, column_index) {
+                    (1, 1) => slice.center,
+                    (1, _) | (_, 1) => slice.edges,
+                    _ => SliceFill::Stretch,
+                };
+                let tiled = fill == SliceFill::Tile;
+                for row in row.tiles(tiled && row_index == 1) {
+                    for column in column.tiles(tiled && column_index == 1) {
+                        self.paste_area_f(
+                            slice.texture,
+                            Rect {
+                                up_left: Point {
+                                    x: column.source_start,
+                                    y: row.source_start,
+                                },
+                                size: Size {
+                                    width: column.source_len,
+                                    height: row.source_len,
+                                },
+                            },
+                            FRect {
+                                up_left: FPoint {
+                                    x: column.target_start,
+                                    y: row.target_start,
+                                },
+                                width: column.target_len,
+                                height: row.target_len,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lens(segments: &[Segment]) -> Vec<(u32, f32)> {
+        segments
+            .iter()
+            .map(|segment| (segment.source_len, segment.target_len))
+            .collect()
+    }
+
+    #[test]
+    fn divide_stretches_middle() {
+        let segments = Segment::divide((5, 30), (10, 10), (0.0, 100.0));
+        assert_eq!(lens(&segments), vec![(10, 10.0), (10, 80.0), (10, 10.0)]);
+        assert_eq!(segments.map(|segment| segment.source_start), [5, 15, 25]);
+        assert_eq!(
+            segments.map(|segment| segment.target_start),
+            [0.0, 10.0, 90.0]
+        );
+    }
+
+    #[test]
+    fn divide_shrinks_insets() {
+        let segments = Segment::divide((0, 30), (10, 10), (0.0, 10.0));
+        assert_eq!(lens(&segments), vec![(10, 5.0), (10, 0.0), (10, 5.0)]);
+        assert_eq!(segments[2].target_start, 5.0);
+    }
+
+    #[test]
+    fn divide_zero_length() {
+        let segments = Segment::divide((0, 0), (0, 0), (0.0, 0.0));
+        assert_eq!(lens(&segments), vec![(0, 0.0); 3]);
+        assert!(segments
+            .iter()
+            .all(|segment| segment.tiles(true).is_empty()));
+        let segments = Segment::divide((0, 20), (5, 5), (3.0, 0.0));
+        assert_eq!(lens(&segments), vec![(5, 0.0), (10, 0.0), (5, 0.0)]);
+        assert!(segments
+            .iter()
+            .all(|segment| segment.tiles(false).is_empty()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn divide_rejects_large_insets() {
+        let _ = Segment::divide((0, 10), (6, 5), (0.0, 10.0));
+    }
+
+    #[test]
+    fn tiles_cut_last_tile() {
+        let segment = Segment {
+            source_start: 0,
+            source_len: 10,
+            target_start: 0.0,
+            target_len: 25.0,
+        };
+        assert_eq!(
+            lens(&segment.tiles(true)),
+            vec![(10, 10.0), (10, 10.0), (5, 5.0)]
+        );
+        assert_eq!(lens(&segment.tiles(false)), vec![(10, 25.0)]);
+    }
+}
@@ -5,11 +5,11 @@ use bitflags::bitflags;
 use crate::texture::Texture;
 use crate::{
     as_raw,
-    geo::{Point, Rect},
+    geo::{FPoint, FRect, Point, Rect},
 };
 use crate::{bind, EnumInt, Sdl};
 
-use super::Renderer;
+use super::{camera::Transform, Renderer};
 
 bitflags! {
     /// Flip mode on pasting from another texture.
@@ -43,25 +43,53 @@ pub struct PasteExOption {
     pub flip: PasteExFlip,
 }
 
+/// An option for [`Paster::paste_ex_f`], positioning with sub-pixel precision.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PasteExFOption {
+    /// The target area of pasting, or whole if `None`.
+    pub target_area: Option<FRect>,
+    /// The degrees of rotating another texture.
+    pub rotation_degrees: f64,
+    /// The center point of pasting.
+    pub center: Option<FPoint>,
+    /// The flip mode of pasting.
+    pub flip: PasteExFlip,
+}
+
 /// A paster controls pasting from a texture.
 ///
-/// This will render when be dropped. So you should re-create on every render.
+/// The paster pastes with the current transform of the renderer, see [`Renderer::push_transform`]. A paster obtained from [`Frame::paster`](super::Frame::paster) pastes into the frame. Otherwise, a paster constructed by [`Paster::new`] will render when be dropped, so you should re-create on every render.
 #[derive(Debug)]
 pub struct Paster<'renderer> {
     renderer: &'renderer Renderer<'renderer>,
+    presents: bool,
 }
 
 impl<'renderer> Drop for Paster<'renderer> {
     fn drop(&mut self) {
-        unsafe { bind::SDL_RenderPresent(self.renderer.as_ptr()) }
+        if self.presents {
+            unsafe { bind::SDL_RenderPresent(self.renderer.as_ptr()) }
+        }
     }
 }
 
 impl<'renderer> Paster<'renderer> {
-    /// Constructs a pen from the renderer [`Renderer`].
+    /// Constructs a paster from the renderer [`Renderer`]. This presents the renderer on dropped.
+    ///
+    /// This is a convenience to draw only textures. Use [`Frame::paster`](super::Frame::paster) to mix with other drawings.
     #[must_use]
     pub fn new(renderer: &'renderer Renderer) -> Self {
-        Self { renderer }
+        Self {
+            renderer,
+            presents: true,
+        }
+    }
+
+    pub(crate) fn view(renderer: &'renderer Renderer) -> Self {
+        Self {
+            renderer,
+            presents: false,
+        }
     }
 
     /// Returns the renderer that the pen is drawing.
@@ -74,6 +102,9 @@ impl<'renderer> Paster<'renderer> {
 impl Paster<'_> {
     /// Pastes the texture into `target_area`, or whole if `None`.
     pub fn paste(&self, texture: &Texture, target_area: Option<Rect>) {
+        if !self.renderer.transform().is_identity() {
+            return self.paste_f(texture, target_area.map(Into::into));
+        }
         let src = texture.clip().map(Into::into);
         let dst = target_area.map(Into::into);
         let ret = unsafe {
@@ -100,6 +131,17 @@ impl Paster<'_> {
             flip,
         }: PasteExOption,
     ) {
+        if !self.renderer.transform().is_identity() {
+            return self.paste_ex_f(
+                texture,
+                PasteExFOption {
+                    target_area: target_area.map(Into::into),
+                    rotation_degrees,
+                    center: center.map(Into::into),
+                    flip,
+                },
+            );
+        }
         let src = texture.clip().map(Into::into);
         let dst = target_area.map(Into::into);
         let center = center.map(Into::into);
@@ -118,4 +160,145 @@ impl Paster<'_> {
             Sdl::error_then_panic("Pasting texture to renderer ex");
         }
     }
+
+    /// Pastes the texture into `target_area` with sub-pixel precision, or whole if `None`.
+    pub fn paste_f(&self, texture: &Texture, target_area: Option<FRect>) {
+        if !self.renderer.transform().is_identity() {
+            return self.paste_ex_f(
+                texture,
+                PasteExFOption {
+                    target_area,
+                    ..PasteExFOption::default()
+                },
+            );
+        }
+        let src = texture.clip().map(Into::into);
+        let dst = target_area.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_RenderCopyF(
+                self.renderer.as_ptr(),
+                texture.as_ptr(),
+                as_raw(&src),
+                as_raw(&dst),
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Pasting texture to renderer");
+        }
+    }
+
+    /// Pastes the texture with options [`PasteExFOption`] with sub-pixel precision.
+    pub fn paste_ex_f(
+        &self,
+        texture: &Texture,
+        PasteExFOption {
+            target_area,
+            rotation_degrees,
+            center,
+            flip,
+        }: PasteExFOption,
+    ) {
+        let transform = self.renderer.transform();
+        let (target_area, rotation_degrees, center, flip) = if transform.is_identity() {
+            (target_area, rotation_degrees, center, flip)
+        } else {
+            let area = target_area.unwrap_or_else(|| self.whole_target());
+            let (area, rotation_degrees, center, flip) =
+                transform_paste(&transform, area, rotation_degrees, center, flip);
+            (Some(area), rotation_degrees, Some(center), flip)
+        };
+        let src = texture.clip().map(Into::into);
+        let dst = target_area.map(Into::into);
+        let center = center.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_RenderCopyExF(
+                self.renderer.as_ptr(),
+                texture.as_ptr(),
+                as_raw(&src),
+                as_raw(&dst),
+                rotation_degrees,
+                as_raw(&center),
+                flip.bits() as EnumInt,
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Pasting texture to renderer ex");
+        }
+    }
+
+    /// Returns the whole area of the render target in the render coordinates, where SDL2 pastes into if no area.
+    pub(crate) fn whole_target(&self) -> FRect {
+        let size = self.renderer.viewport().size;
+        FRect {
+            up_left: FPoint::default(),
+            width: size.width as f32,
+            height: size.height as f32,
+        }
+    }
+
+    /// Pastes `source_area` of the texture into `target_area` with the current transform, ignoring the clip of the texture.
+    pub(crate) fn paste_area_f(&self, texture: &Texture, source_area: Rect, target_area: FRect) {
+        let transform = self.renderer.transform();
+        let src = source_area.into();
+        let ret = if transform.is_identity() {
+            unsafe {
+                bind::SDL_RenderCopyF(
+                    self.renderer.as_ptr(),
+                    texture.as_ptr(),
+                    &src,
+                    &target_area.into(),
+                )
+            }
+        } else {
+            let (area, rotation_degrees, center, flip) =
+                transform_paste(&transform, target_area, 0.0, None, PasteExFlip::empty());
+            unsafe {
+                bind::SDL_RenderCopyExF(
+                    self.renderer.as_ptr(),
+                    texture.as_ptr(),
+                    &src,
+                    &area.into(),
+                    rotation_degrees,
+                    &center.into(),
+                    flip.bits() as EnumInt,
+                )
+            }
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Pasting texture to renderer");
+        }
+    }
+}
+
+/// Transforms the target area and the options of pasting. Rotation with non-uniform scaling is approximated, because a rectangle cannot be sheared on pasting.
+fn transform_paste(
+    transform: &Transform,
+    area: FRect,
+    rotation_degrees: f64,
+    center: Option<FPoint>,
+    mut flip: PasteExFlip,
+) -> (FRect, f64, FPoint, PasteExFlip) {
+    let center = center.unwrap_or(FPoint {
+        x: area.width / 2.0,
+        y: area.height / 2.0,
+    });
+    let pivot = transform.apply(area.up_left + center);
+    let (horizontal, vertical, degrees) = transform.decompose();
+    let (center_y, rotation_degrees) = if vertical < 0.0 {
+        flip ^= PasteExFlip::VERTICAL;
+        (area.height - center.y, -rotation_degrees)
+    } else {
+        (center.y, rotation_degrees)
+    };
+    let vertical = vertical.abs();
+    let center = FPoint {
+        x: center.x * horizontal,
+        y: center_y * vertical,
+    };
+    let area = FRect {
+        up_left: pivot - center,
+        width: area.width * horizontal,
+        height: area.height * vertical,
+    };
+    (area, rotation_degrees + f64::from(degrees), center, flip)
 }
@@ -1,30 +1,43 @@
 //! Rendering geometries and setting colors.
 
-use crate::color::{BlendMode, Rgb};
-use crate::geo::Rect;
+use crate::color::{BlendMode, Rgb, Rgba};
+use crate::geo::{FLine, FPoint, FRect, Rect};
 use crate::video::geo::{Line, Point};
 use crate::{bind, Sdl};
 
-use super::Renderer;
+use super::{geometry::Vertex, Renderer};
 
 /// A pen controls its color and renders geometries to the renderer.
 ///
-/// This will render when be dropped. So you should re-create on every render.
+/// The pen draws with the current transform of the renderer, see [`Renderer::push_transform`]. A pen obtained from [`Frame::pen`](super::Frame::pen) draws into the frame. Otherwise, a pen constructed by [`Pen::new`] clears the renderer on constructed and will render when be dropped, so you should re-create on every render.
 #[derive(Debug)]
 pub struct Pen<'renderer> {
     renderer: &'renderer Renderer<'renderer>,
+    presents: bool,
 }
 
 impl<'renderer> Pen<'renderer> {
-    /// Constructs a pen from the renderer [`Renderer`].
+    /// Constructs a pen from the renderer [`Renderer`]. This clears the renderer, and presents it on dropped.
+    ///
+    /// This is a convenience to draw only geometries. Use [`Frame::pen`](super::Frame::pen) to mix with other drawings.
     #[must_use]
     pub fn new(renderer: &'renderer Renderer) -> Self {
-        let this = Self { renderer };
+        let this = Self {
+            renderer,
+            presents: true,
+        };
         this.set_color(Rgb { r: 0, g: 0, b: 0 });
         this.clear();
         this
     }
 
+    pub(super) fn view(renderer: &'renderer Renderer) -> Self {
+        Self {
+            renderer,
+            presents: false,
+        }
+    }
+
     /// Returns the renderer that the pen is drawing.
     #[must_use]
     pub fn renderer(&self) -> &Renderer {
@@ -87,6 +100,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draws the line.
     pub fn line(&self, line: Line) {
+        if !self.renderer.transform().is_identity() {
+            return self.line_f(line.into());
+        }
         let ret = unsafe {
             bind::SDL_RenderDrawLine(
                 self.renderer.as_ptr(),
@@ -103,6 +119,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draws the lines.
     pub fn lines(&self, points: impl IntoIterator<Item = Point>) {
+        if !self.renderer.transform().is_identity() {
+            return self.lines_f(points.into_iter().map(Into::into));
+        }
         let points: Vec<_> = points.into_iter().map(Into::into).collect();
         let ret = unsafe {
             bind::SDL_RenderDrawLines(self.renderer.as_ptr(), points.as_ptr(), points.len() as i32)
@@ -114,6 +133,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the point.
     pub fn point(&self, point: Point) {
+        if !self.renderer.transform().is_identity() {
+            return self.point_f(point.into());
+        }
         let ret = unsafe { bind::SDL_RenderDrawPoint(self.renderer.as_ptr(), point.x, point.y) };
         if ret != 0 {
             Sdl::error_then_panic("Sdl pen point")
@@ -122,6 +144,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the points.
     pub fn points(&self, points: impl IntoIterator<Item = Point>) {
+        if !self.renderer.transform().is_identity() {
+            return self.points_f(points.into_iter().map(Into::into));
+        }
         let points: Vec<_> = points.into_iter().map(Into::into).collect();
         let ret = unsafe {
             bind::SDL_RenderDrawPoints(self.renderer.as_ptr(), points.as_ptr(), points.len() as i32)
@@ -133,6 +158,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the rectangle only lines.
     pub fn stroke_rect(&self, rect: Rect) {
+        if !self.renderer.transform().is_identity() {
+            return self.stroke_rect_f(rect.into());
+        }
         let ret = unsafe { bind::SDL_RenderDrawRect(self.renderer.as_ptr(), &rect.into()) };
         if ret != 0 {
             Sdl::error_then_panic("Sdl pen rect")
@@ -141,6 +169,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the rectangles only lines.
     pub fn stroke_rects(&self, rects: impl IntoIterator<Item = Rect>) {
+        if !self.renderer.transform().is_identity() {
+            return self.stroke_rects_f(rects.into_iter().map(Into::into));
+        }
         let rects: Vec<_> = rects.into_iter().map(Into::into).collect();
         let ret = unsafe {
             bind::SDL_RenderDrawRects(self.renderer.as_ptr(), rects.as_ptr(), rects.len() as i32)
@@ -152,6 +183,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the filled rectangle.
     pub fn fill_rect(&self, rect: Rect) {
+        if !self.renderer.transform().is_identity() {
+            return self.fill_rect_f(rect.into());
+        }
         let ret = unsafe { bind::SDL_RenderFillRect(self.renderer.as_ptr(), &rect.into()) };
         if ret != 0 {
             Sdl::error_then_panic("Sdl pen rect")
@@ -160,6 +194,9 @@ impl<'renderer> Pen<'renderer> {
 
     /// Draw the filled rectangles.
     pub fn fill_rects(&self, rects: impl IntoIterator<Item = Rect>) {
+        if !self.renderer.transform().is_identity() {
+            return self.fill_rects_f(rects.into_iter().map(Into::into));
+        }
         let rects: Vec<_> = rects.into_iter().map(Into::into).collect();
         let ret = unsafe {
             bind::SDL_RenderFillRects(self.renderer.as_ptr(), rects.as_ptr(), rects.len() as i32)
@@ -168,10 +205,161 @@ impl<'renderer> Pen<'renderer> {
             Sdl::error_then_panic("Sdl pen rects")
         }
     }
+
+    /// Draws the line with sub-pixel precision.
+    pub fn line_f(&self, line: FLine) {
+        let transform = self.renderer.transform();
+        let (start, end) = (transform.apply(line.start), transform.apply(line.end));
+        let ret = unsafe {
+            bind::SDL_RenderDrawLineF(self.renderer.as_ptr(), start.x, start.y, end.x, end.y)
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen line")
+        }
+    }
+
+    /// Draws the lines with sub-pixel precision.
+    pub fn lines_f(&self, points: impl IntoIterator<Item = FPoint>) {
+        let transform = self.renderer.transform();
+        let points: Vec<_> = points.into_iter().map(|p| transform.apply(p)).collect();
+        self.transformed_lines(&points);
+    }
+
+    fn transformed_lines(&self, points: &[FPoint]) {
+        let points: Vec<bind::SDL_FPoint> = points.iter().copied().map(Into::into).collect();
+        let ret = unsafe {
+            bind::SDL_RenderDrawLinesF(self.renderer.as_ptr(), points.as_ptr(), points.len() as i32)
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen lines")
+        }
+    }
+
+    /// Draw the point with sub-pixel precision.
+    pub fn point_f(&self, point: FPoint) {
+        let point = self.renderer.transform().apply(point);
+        let ret = unsafe { bind::SDL_RenderDrawPointF(self.renderer.as_ptr(), point.x, point.y) };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen point")
+        }
+    }
+
+    /// Draw the points with sub-pixel precision.
+    pub fn points_f(&self, points: impl IntoIterator<Item = FPoint>) {
+        let transform = self.renderer.transform();
+        let points: Vec<bind::SDL_FPoint> = points
+            .into_iter()
+            .map(|p| transform.apply(p).into())
+            .collect();
+        let ret = unsafe {
+            bind::SDL_RenderDrawPointsF(
+                self.renderer.as_ptr(),
+                points.as_ptr(),
+                points.len() as i32,
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen points")
+        }
+    }
+
+    /// Draw the rectangle only lines with sub-pixel precision.
+    pub fn stroke_rect_f(&self, rect: FRect) {
+        self.stroke_rects_f(std::iter::once(rect));
+    }
+
+    /// Draw the rectangles only lines with sub-pixel precision.
+    pub fn stroke_rects_f(&self, rects: impl IntoIterator<Item = FRect>) {
+        let transform = self.renderer.transform();
+        if !transform.is_axis_aligned() {
+            for rect in rects {
+                let [a, b, c, d] = transform.corners(rect);
+                self.transformed_lines(&[a, b, c, d, a]);
+            }
+            return;
+        }
+        let rects: Vec<bind::SDL_FRect> = rects
+            .into_iter()
+            .map(|rect| transform.apply_rect(rect).into())
+            .collect();
+        let ret = unsafe {
+            bind::SDL_RenderDrawRectsF(self.renderer.as_ptr(), rects.as_ptr(), rects.len() as i32)
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen rects")
+        }
+    }
+
+    /// Draw the filled rectangle with sub-pixel precision.
+    pub fn fill_rect_f(&self, rect: FRect) {
+        self.fill_rects_f(std::iter::once(rect));
+    }
+
+    /// Draw the filled rectangles with sub-pixel precision.
+    pub fn fill_rects_f(&self, rects: impl IntoIterator<Item = FRect>) {
+        let transform = self.renderer.transform();
+        if !transform.is_axis_aligned() {
+            let color = self.rgba();
+            let vertices: Vec<_> = rects
+                .into_iter()
+                .flat_map(|rect| {
+                    let [a, b, c, d] = transform.corners(rect);
+                    [a, b, c, c, d, a]
+                })
+                .map(|position| Vertex {
+                    position,
+                    color,
+                    tex_coord: FPoint::default(),
+                })
+                .collect();
+            if self
+                .renderer
+                .render_geometry(None, &vertices, None)
+                .is_err()
+            {
+                Sdl::error_then_panic("Sdl pen rects")
+            }
+            return;
+        }
+        let rects: Vec<bind::SDL_FRect> = rects
+            .into_iter()
+            .map(|rect| transform.apply_rect(rect).into())
+            .collect();
+        let ret = unsafe {
+            bind::SDL_RenderFillRectsF(self.renderer.as_ptr(), rects.as_ptr(), rects.len() as i32)
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen rects")
+        }
+    }
+
+    pub(super) fn rgba(&self) -> Rgba {
+        let mut color = Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        let ret = unsafe {
+            bind::SDL_GetRenderDrawColor(
+                self.renderer.as_ptr(),
+                &mut color.r,
+                &mut color.g,
+                &mut color.b,
+                &mut color.a,
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Sdl pen color")
+        }
+        color
+    }
 }
 
 impl<'renderer> Drop for Pen<'renderer> {
     fn drop(&mut self) {
-        unsafe { bind::SDL_RenderPresent(self.renderer.as_ptr()) }
+        if self.presents {
+            unsafe { bind::SDL_RenderPresent(self.renderer.as_ptr()) }
+        }
     }
 }
@@ -0,0 +1,632 @@
+//! Vector shapes tessellated into triangles, drawn by [`Pen`].
+
+use std::f32::consts::PI;
+
+use typed_builder::TypedBuilder;
+
+use crate::color::Rgba;
+use crate::geo::{FLine, FPoint, FRect};
+use crate::Sdl;
+
+use super::geometry::{Vertex, VertexIndices};
+use super::pen::Pen;
+
+/// The maximum distance between the curve and its segments.
+const TOLERANCE: f32 = 0.25;
+/// The maximum ratio of the miter length to the half of the stroke width.
+const MITER_LIMIT: f32 = 4.0;
+
+/// A polyline in the shape.
+#[derive(Debug, Clone, PartialEq)]
+struct Contour {
+    points: Vec<FPoint>,
+    closed: bool,
+}
+
+/// A vector shape made of contours, to be filled or stroked by [`Pen`]. The curves are flattened into line segments on constructed.
+///
+/// The angles are in degrees, clockwise on the screen from the positive x axis.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Shape {
+    contours: Vec<Contour>,
+}
+
+impl Shape {
+    /// Constructs the circle.
+    pub fn circle(center: FPoint, radius: f32) -> Self {
+        Self::ellipse(center, radius, radius)
+    }
+
+    /// Constructs the ellipse with the horizontal and vertical radius.
+    pub fn ellipse(center: FPoint, radius_x: f32, radius_y: f32) -> Self {
+        let mut points = arc_points(center, radius_x, radius_y, 0.0, 2.0 * PI);
+        points.pop();
+        Self::contour(points, true)
+    }
+
+    /// Constructs the open arc of the circle from `start_degrees` to `end_degrees`.
+    pub fn arc(center: FPoint, radius: f32, start_degrees: f32, end_degrees: f32) -> Self {
+        let points = arc_points(
+            center,
+            radius,
+            radius,
+            start_degrees.to_radians(),
+            (end_degrees - start_degrees).to_radians(),
+        );
+        Self::contour(points, false)
+    }
+
+    /// Constructs the pie slice of the circle from `start_degrees` to `end_degrees`.
+    pub fn pie(center: FPoint, radius: f32, start_degrees: f32, end_degrees: f32) -> Self {
+        let mut points = vec![center];
+        points.extend(arc_points(
+            center,
+            radius,
+            radius,
+            start_degrees.to_radians(),
+            (end_degrees - start_degrees).to_radians(),
+        ));
+        Self::contour(points, true)
+    }
+
+    /// Constructs the rectangle with rounded corners. The radius is limited to the half of the shorter side.
+    pub fn rounded_rect(rect: FRect, radius: f32) -> Self {
+        let radius = radius.min(rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+        let FRect {
+            up_left,
+            width,
+            height,
+        } = rect;
+        let corners = [
+            (up_left.offset(radius, radius), PI),
+            (up_left.offset(width - radius, radius), 1.5 * PI),
+            (up_left.offset(width - radius, height - radius), 0.0),
+            (up_left.offset(radius, height - radius), 0.5 * PI),
+        ];
+        let points = corners
+            .iter()
+            .flat_map(|&(center, start)| arc_points(center, radius, radius, start, 0.5 * PI))
+            .collect();
+        Self::contour(points, true)
+    }
+
+    /// Constructs the closed polygon, which can be concave.
+    pub fn polygon(points: impl IntoIterator<Item = FPoint>) -> Self {
+        Self::contour(points.into_iter().collect(), true)
+    }
+
+    /// Constructs the open polyline.
+    pub fn polyline(points: impl IntoIterator<Item = FPoint>) -> Self {
+        Self::contour(points.into_iter().collect(), false)
+    }
+
+    /// Constructs the line.
+    pub fn line(FLine { start, end }: FLine) -> Self {
+        Self::contour(vec![start, end], false)
+    }
+
+    /// Appends the contours of `other` into the shape.
+    pub fn append(mut self, other: Self) -> Self {
+        self.contours.extend(other.contours);
+        self
+    }
+
+    fn contour(points: Vec<FPoint>, closed: bool) -> Self {
+        Self {
+            contours: vec![Contour { points, closed }],
+        }
+    }
+}
+
+/// Flattens the arc of the ellipse into points, including the both ends.
+fn arc_points(center: FPoint, radius_x: f32, radius_y: f32, start: f32, sweep: f32) -> Vec<FPoint> {
+    let radius = radius_x.abs().max(radius_y.abs());
+    let step = if TOLERANCE < radius {
+        2.0 * (1.0 - TOLERANCE / radius).acos()
+    } else {
+        PI / 2.0
+    };
+    let segments = ((sweep.abs() / step).ceil() as usize).clamp(1, 1024);
+    (0..=segments)
+        .map(|i| {
+            let angle = start + sweep * i as f32 / segments as f32;
+            FPoint {
+                x: center.x + radius_x * angle.cos(),
+                y: center.y + radius_y * angle.sin(),
+            }
+        })
+        .collect()
+}
+
+/// A shape of the ends of open strokes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// Ends at the end points.
+    Butt,
+    /// Extends the half of the width beyond the end points.
+    Square,
+    /// Extends with the semicircle.
+    Round,
+}
+
+impl Default for LineCap {
+    fn default() -> Self {
+        Self::Butt
+    }
+}
+
+/// A shape of the corners of strokes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// Extends the outer edges to meet, or falls back to [`LineJoin::Bevel`] if too sharp.
+    Miter,
+    /// Cuts the corner.
+    Bevel,
+    /// Rounds the corner.
+    Round,
+}
+
+impl Default for LineJoin {
+    fn default() -> Self {
+        Self::Miter
+    }
+}
+
+/// A style of stroking the shape.
+#[derive(Debug, Clone, Copy, PartialEq, TypedBuilder)]
+pub struct StrokeStyle {
+    /// The width of the stroke.
+    #[builder(default = 1.0)]
+    pub width: f32,
+    /// The shape of the ends of open contours.
+    #[builder(default)]
+    pub cap: LineCap,
+    /// The shape of the corners.
+    #[builder(default)]
+    pub join: LineJoin,
+    /// Whether the edges along the segments are smoothed.
+    #[builder(default)]
+    pub anti_alias: bool,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Triangles to render at once.
+#[derive(Debug, Default)]
+struct Mesh {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl Mesh {
+    fn push(&mut self, position: FPoint, color: Rgba) -> u32 {
+        self.vertices.push(Vertex {
+            position,
+            color,
+            tex_coord: FPoint::default(),
+        });
+        self.vertices.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend_from_slice(&[a, b, c, c, d, a]);
+    }
+
+    fn fan(&mut self, center: FPoint, radius: f32, start: f32, sweep: f32, color: Rgba) {
+        let center_index = self.push(center, color);
+        let indices: Vec<_> = arc_points(center, radius, radius, start, sweep)
+            .into_iter()
+            .map(|point| self.push(point, color))
+            .collect();
+        for pair in indices.windows(2) {
+            self.triangle(center_index, pair[0], pair[1]);
+        }
+    }
+}
+
+fn dot(a: FPoint, b: FPoint) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+fn cross(a: FPoint, b: FPoint) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn normalize(v: FPoint) -> FPoint {
+    let len = v.length();
+    if len == 0.0 {
+        v
+    } else {
+        v / len
+    }
+}
+
+/// Returns the normal rotated 90 degrees counter-clockwise on the screen.
+fn normal_of(direction: FPoint) -> FPoint {
+    FPoint {
+        x: direction.y,
+        y: -direction.x,
+    }
+}
+
+fn angle_of(v: FPoint) -> f32 {
+    v.y.atan2(v.x)
+}
+
+/// Removes the consecutive duplicated points, and the last one same as the first if closed.
+fn dedup_points(points: &[FPoint], closed: bool) -> Vec<FPoint> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && 1 < points.len() && points.first() == points.last() {
+        points.pop();
+    }
+    points
+}
+
+fn signed_area(points: &[FPoint]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| cross(points[i], points[(i + 1) % n]))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn is_in_triangle(p: FPoint, [a, b, c]: [FPoint; 3], sign: f32) -> bool {
+    sign * cross(b - a, p - a) >= 0.0
+        && sign * cross(c - b, p - b) >= 0.0
+        && sign * cross(a - c, p - c) >= 0.0
+}
+
+/// Triangulates the simple polygon by ear clipping. Self-intersecting polygons are triangulated approximately.
+fn triangulate(points: &[FPoint]) -> Vec<[usize; 3]> {
+    let sign = if 0.0 <= signed_area(points) {
+        1.0
+    } else {
+        -1.0
+    };
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    let mut index = 0;
+    let mut failures = 0;
+    while 3 < remaining.len() {
+        let n = remaining.len();
+        index %= n;
+        let (a, b, c) = (
+            remaining[(index + n - 1) % n],
+            remaining[index],
+            remaining[(index + 1) % n],
+        );
+        let corner = [points[a], points[b], points[c]];
+        let is_convex = 0.0 < sign * cross(corner[1] - corner[0], corner[2] - corner[1]);
+        let is_ear = is_convex
+            && remaining
+                .iter()
+                .filter(|&&other| other != a && other != b && other != c)
+                .all(|&other| !is_in_triangle(points[other], corner, sign));
+        if is_ear || n <= failures {
+            triangles.push([a, b, c]);
+            remaining.remove(index);
+            failures = 0;
+        } else {
+            index += 1;
+            failures += 1;
+        }
+    }
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    triangles
+}
+
+fn fill_contour(mesh: &mut Mesh, points: &[FPoint], color: Rgba, anti_alias: bool) {
+    let points = dedup_points(points, true);
+    if points.len() < 3 {
+        return;
+    }
+    let triangles = triangulate(&points);
+    let base = mesh.vertices.len() as u32;
+    if !anti_alias {
+        for &point in &points {
+            mesh.push(point, color);
+        }
+        for [a, b, c] in triangles {
+            mesh.triangle(base + a as u32, base + b as u32, base + c as u32);
+        }
+        return;
+    }
+
+    let n = points.len();
+    let sign = if 0.0 <= signed_area(&points) {
+        1.0
+    } else {
+        -1.0
+    };
+    let outward = |from: FPoint, to: FPoint| normal_of(normalize(to - from)) * sign;
+    let transparent = Rgba { a: 0, ..color };
+    let offsets: Vec<_> = (0..n)
+        .map(|i| {
+            let (prev, point, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let average = (outward(prev, point) + outward(point, next)) / 2.0;
+            let len2 = dot(average, average);
+            if 1e-6 < len2 {
+                average * (1.0 / len2).min(100.0) * 0.5
+            } else {
+                average * 0.5
+            }
+        })
+        .collect();
+    for (&point, &offset) in points.iter().zip(&offsets) {
+        mesh.push(point - offset, color);
+    }
+    for (&point, &offset) in points.iter().zip(&offsets) {
+        mesh.push(point + offset, transparent);
+    }
+    for [a, b, c] in triangles {
+        mesh.triangle(base + a as u32, base + b as u32, base + c as u32);
+    }
+    for i in 0..n as u32 {
+        let j = (i + 1) % n as u32;
+        mesh.quad(base + i, base + j, base + n as u32 + j, base + n as u32 + i);
+    }
+}
+
+fn stroke_segment(
+    mesh: &mut Mesh,
+    (start, end): (FPoint, FPoint),
+    half_width: f32,
+    color: Rgba,
+    anti_alias: bool,
+) {
+    let normal = normal_of(normalize(end - start));
+    if !anti_alias {
+        let offset = normal * half_width;
+        let a = mesh.push(start + offset, color);
+        let b = mesh.push(end + offset, color);
+        let c = mesh.push(end - offset, color);
+        let d = mesh.push(start - offset, color);
+        mesh.quad(a, b, c, d);
+        return;
+    }
+    let transparent = Rgba { a: 0, ..color };
+    let core = normal * (half_width - 0.5).max(0.0);
+    let fringe = normal * (half_width + 0.5);
+    let outer_start = mesh.push(start + fringe, transparent);
+    let outer_end = mesh.push(end + fringe, transparent);
+    let core_start = mesh.push(start + core, color);
+    let core_end = mesh.push(end + core, color);
+    let inner_start = mesh.push(start - core, color);
+    let inner_end = mesh.push(end - core, color);
+    let back_start = mesh.push(start - fringe, transparent);
+    let back_end = mesh.push(end - fringe, transparent);
+    mesh.quad(outer_start, outer_end, core_end, core_start);
+    mesh.quad(core_start, core_end, inner_end, inner_start);
+    mesh.quad(inner_start, inner_end, back_end, back_start);
+}
+
+fn stroke_join(
+    mesh: &mut Mesh,
+    (prev, point, next): (FPoint, FPoint, FPoint),
+    half_width: f32,
+    join: LineJoin,
+    color: Rgba,
+) {
+    let (incoming, outgoing) = (normalize(point - prev), normalize(next - point));
+    let turn = cross(incoming, outgoing);
+    if turn.abs() < 1e-6 && 0.0 < dot(incoming, outgoing) {
+        return;
+    }
+    let side = if 0.0 < turn { 1.0 } else { -1.0 };
+    let (normal_in, normal_out) = (normal_of(incoming) * side, normal_of(outgoing) * side);
+    let (outer_in, outer_out) = (
+        point + normal_in * half_width,
+        point + normal_out * half_width,
+    );
+    match join {
+        LineJoin::Round => {
+            let start = angle_of(normal_in);
+            let mut sweep = angle_of(normal_out) - start;
+            if PI < sweep {
+                sweep -= 2.0 * PI;
+            } else if sweep < -PI {
+                sweep += 2.0 * PI;
+            }
+            mesh.fan(point, half_width, start, sweep, color);
+        }
+        LineJoin::Miter => {
+            let bisector = normalize(normal_in + normal_out);
+            let cos_half = dot(bisector, normal_in);
+            if 1.0 / MITER_LIMIT < cos_half {
+                let center = mesh.push(point, color);
+                let a = mesh.push(outer_in, color);
+                let tip = mesh.push(point + bisector * (half_width / cos_half), color);
+                let b = mesh.push(outer_out, color);
+                mesh.quad(center, a, tip, b);
+            } else {
+                stroke_join(
+                    mesh,
+                    (prev, point, next),
+                    half_width,
+                    LineJoin::Bevel,
+                    color,
+                );
+            }
+        }
+        LineJoin::Bevel => {
+            let center = mesh.push(point, color);
+            let a = mesh.push(outer_in, color);
+            let b = mesh.push(outer_out, color);
+            mesh.triangle(center, a, b);
+        }
+    }
+}
+
+fn stroke_contour(mesh: &mut Mesh, contour: &Contour, style: &StrokeStyle, color: Rgba) {
+    let points = dedup_points(&contour.points, contour.closed);
+    let half_width = style.width / 2.0;
+    let solid_half_width = if style.anti_alias {
+        (half_width - 0.5).max(0.0)
+    } else {
+        half_width
+    };
+    let n = points.len();
+    if n < 2 {
+        if let (Some(&point), LineCap::Round) = (points.first(), style.cap) {
+            mesh.fan(point, solid_half_width, 0.0, 2.0 * PI, color);
+        }
+        return;
+    }
+    let segments = if contour.closed { n } else { n - 1 };
+    for i in 0..segments {
+        let (mut start, mut end) = (points[i], points[(i + 1) % n]);
+        if !contour.closed && style.cap == LineCap::Square {
+            let direction = normalize(end - start) * half_width;
+            if i == 0 {
+                start -= direction;
+            }
+            if i == segments - 1 {
+                end += direction;
+            }
+        }
+        stroke_segment(mesh, (start, end), half_width, color, style.anti_alias);
+    }
+    let joins = if contour.closed { 0..n } else { 1..n - 1 };
+    for i in joins {
+        let corner = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        stroke_join(mesh, corner, solid_half_width, style.join, color);
+    }
+    if !contour.closed && style.cap == LineCap::Round {
+        let start_normal = normal_of(normalize(points[1] - points[0]));
+        mesh.fan(
+            points[0],
+            solid_half_width,
+            angle_of(start_normal),
+            -PI,
+            color,
+        );
+        let end_normal = normal_of(normalize(points[n - 1] - points[n - 2]));
+        mesh.fan(
+            points[n - 1],
+            solid_half_width,
+            angle_of(end_normal),
+            PI,
+            color,
+        );
+    }
+}
+
+impl Pen<'_> {
+    /// Fills the shape with the drawing color, open contours are closed implicitly. Each contour is filled independently, and self-intersecting contours may be filled incorrectly.
+    ///
+    /// If `anti_alias` is true, the edges are smoothed with the transparent fringe, which needs the blend mode such as [`BlendMode::AlphaBlend`](crate::color::BlendMode::AlphaBlend).
+    pub fn fill_shape(&self, shape: &Shape, anti_alias: bool) {
+        let color = self.rgba();
+        let mut mesh = Mesh::default();
+        for contour in &shape.contours {
+            fill_contour(&mut mesh, &contour.points, color, anti_alias);
+        }
+        self.render_mesh(mesh);
+    }
+
+    /// Strokes the contours of the shape with the drawing color and `style`. The overlapped parts of the translucent stroke are blended twice.
+    pub fn stroke_shape(&self, shape: &Shape, style: &StrokeStyle) {
+        let color = self.rgba();
+        let mut mesh = Mesh::default();
+        for contour in &shape.contours {
+            stroke_contour(&mut mesh, contour, style, color);
+        }
+        self.render_mesh(mesh);
+    }
+
+    fn render_mesh(&self, mut mesh: Mesh) {
+        let transform = self.renderer().transform();
+        for vertex in &mut mesh.vertices {
+            vertex.position = transform.apply(vertex.position);
+        }
+        let ret = self.renderer().render_geometry(
+            None,
+            &mesh.vertices,
+            Some(VertexIndices::U32(&mesh.indices)),
+        );
+        if ret.is_err() {
+            Sdl::error_then_panic("Sdl pen shape")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f32, f32)]) -> Vec<FPoint> {
+        coords.iter().map(|&(x, y)| FPoint { x, y }).collect()
+    }
+
+    /// Asserts the triangles cover the polygon without flipped ones.
+    fn assert_covers(points: &[FPoint], triangles: &[[usize; 3]]) {
+        assert_eq!(triangles.len(), points.len() - 2);
+        let area = signed_area(points);
+        let mut sum = 0.0;
+        for &[a, b, c] in triangles {
+            let triangle = cross(points[b] - points[a], points[c] - points[a]) / 2.0;
+            assert!(0.0 <= triangle * area.signum());
+            sum += triangle;
+        }
+        assert!((sum - area).abs() < 1e-4);
+    }
+
+    #[test]
+    fn triangulate_convex() {
+        let points = points(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn triangulate_clockwise() {
+        let points = points(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)]);
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn triangulate_concave() {
+        let l_shape = points(&[
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 4.0),
+            (0.0, 4.0),
+        ]);
+        assert_covers(&l_shape, &triangulate(&l_shape));
+        let notched = points(&[(0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_covers(&notched, &triangulate(&notched));
+    }
+
+    #[test]
+    fn triangulate_collinear() {
+        let points = points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn triangulate_degenerate() {
+        let points = points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]);
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn dedup_closed_points() {
+        let deduped = dedup_points(
+            &points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            true,
+        );
+        assert_eq!(deduped, points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]));
+    }
+}
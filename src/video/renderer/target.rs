@@ -0,0 +1,134 @@
+//! Switching the render target into a texture in a scope.
+
+use std::marker::PhantomData;
+
+use crate::geo::{Point, Rect, Scale};
+use crate::texture::{QueryExt, Texture, TextureAccess};
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::{pen::Pen, Frame, Paster, Renderer};
+
+/// A guard switching the render target into the texture. The previous render target, viewport and scale are restored on dropped.
+///
+/// Guards can be nested, so the render target returns to the outer texture when the inner guard was dropped.
+pub struct TargetGuard<'renderer, 'texture> {
+    renderer: &'renderer Renderer<'renderer>,
+    prev_target: *mut bind::SDL_Texture,
+    /// The previous viewport, or `None` if it was the default following the size of the target.
+    prev_viewport: Option<Rect>,
+    prev_scale: Scale,
+    _phantom: PhantomData<&'texture mut ()>,
+}
+
+impl std::fmt::Debug for TargetGuard<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TargetGuard")
+            .field("renderer", &self.renderer)
+            .field("prev_viewport", &self.prev_viewport)
+            .field("prev_scale", &self.prev_scale)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'renderer, 'texture> TargetGuard<'renderer, 'texture> {
+    /// Switches the render target of `renderer` into `texture`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `texture` was not created with [`TextureAccess::Target`], or the renderer does not support render targets.
+    pub fn new(renderer: &'renderer Renderer, texture: &'texture mut Texture) -> Result<Self> {
+        if texture.access() != TextureAccess::Target {
+            return Err(SdlError::Others {
+                msg: "the texture was not created with `TextureAccess::Target`".into(),
+            });
+        }
+        if unsafe { bind::SDL_RenderTargetSupported(renderer.as_ptr()) } == 0 {
+            return Err(SdlError::UnsupportedFeature);
+        }
+        let prev_target = unsafe { bind::SDL_GetRenderTarget(renderer.as_ptr()) };
+        let prev_scale = renderer.scale();
+        let prev_viewport = Some(renderer.viewport())
+            .filter(|&viewport| !is_default_viewport(renderer, viewport, prev_scale));
+        let ret = unsafe { bind::SDL_SetRenderTarget(renderer.as_ptr(), texture.as_ptr()) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+        Ok(Self {
+            renderer,
+            prev_target,
+            prev_viewport,
+            prev_scale,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the renderer whose target is switched.
+    #[must_use]
+    pub fn renderer(&self) -> &Renderer {
+        self.renderer
+    }
+
+    /// Returns the pen to draw geometries into the texture.
+    #[must_use]
+    pub fn pen(&self) -> Pen {
+        Pen::view(self.renderer)
+    }
+
+    /// Returns the paster to paste textures into the texture.
+    #[must_use]
+    pub fn paster(&self) -> Paster {
+        Paster::view(self.renderer)
+    }
+}
+
+impl Drop for TargetGuard<'_, '_> {
+    fn drop(&mut self) {
+        let ret = unsafe { bind::SDL_SetRenderTarget(self.renderer.as_ptr(), self.prev_target) };
+        if ret != 0 {
+            Sdl::error_then_panic("Restoring renderer target");
+        }
+        // The viewport is set in the current scale, so the scale must be restored first.
+        self.renderer.set_scale(self.prev_scale);
+        self.renderer.set_viewport(self.prev_viewport);
+    }
+}
+
+/// Returns whether `viewport` is the default one covering the whole output of the current target.
+fn is_default_viewport(renderer: &Renderer, viewport: Rect, scale: Scale) -> bool {
+    let output = match renderer.output_size() {
+        Ok(output) => output,
+        Err(_) => return false,
+    };
+    viewport.up_left == Point::default()
+        && viewport.size.width == (output.width as f32 / scale.horizontal) as u32
+        && viewport.size.height == (output.height as f32 / scale.vertical) as u32
+}
+
+impl Renderer<'_> {
+    /// Switches the render target into `texture`, and calls `draw` with the [`Frame`] drawing into the texture. The previous render target, viewport and scale are restored after that.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `texture` was not created with [`TextureAccess::Target`], or the renderer does not support render targets.
+    pub fn with_target<R>(
+        &self,
+        texture: &mut Texture,
+        draw: impl FnOnce(&Frame) -> R,
+    ) -> Result<R> {
+        let _guard = TargetGuard::new(self, texture)?;
+        let frame = Frame::on_target(self);
+        Ok(draw(&frame))
+    }
+
+    /// Switches the render target into `texture` until the returned guard is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `texture` was not created with [`TextureAccess::Target`], or the renderer does not support render targets.
+    pub fn target<'texture>(
+        &self,
+        texture: &'texture mut Texture,
+    ) -> Result<TargetGuard<'_, 'texture>> {
+        TargetGuard::new(self, texture)
+    }
+}
@@ -2,30 +2,120 @@
 
 use std::ptr::NonNull;
 
-use crate::color::pixel::Pixel;
+use crate::color::pixel::{kind::PixelFormatKind, Pixel, PixelFormat};
 use crate::color::{BlendMode, Rgb};
-use crate::geo::{Point, Rect};
+use crate::geo::{Point, Rect, Size};
 use crate::{as_raw, color::pixel::palette::Palette};
-use crate::{bind, Sdl};
+use crate::{bind, Result, Sdl, SdlError};
 pub use bind::SDL_Surface as RawSurface;
 
 pub mod alpha;
 pub mod blend;
 pub mod bmp;
+pub mod borrowed;
+pub mod canvas;
 pub mod clipped;
 pub mod cloned;
 pub mod color;
+pub mod color_key;
+pub mod compare;
+pub mod lock;
 pub mod owned;
 pub mod rle;
+pub mod transform;
 pub mod window;
 
 use alpha::AlphaMod;
 use blend::Blended;
+use canvas::Canvas;
 use clipped::Clipped;
 use cloned::Cloned;
 use color::ColorMod;
+use color_key::ColorKey;
+use lock::Lock;
+use owned::Owned;
 use rle::Rle;
 
+/// A filter on stretching the surface by [`Surface::stretch_to`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StretchFilter {
+    /// Picks the nearest pixel, fast but jaggy.
+    Nearest,
+    /// Interpolates the pixels linearly, smooth but only for 32-bit formats.
+    Linear,
+}
+
+/// Returns the whole area of the surface.
+fn whole_area(surface: &(impl Surface + ?Sized)) -> Rect {
+    let raw = unsafe { surface.as_ptr().as_ref() };
+    Rect {
+        up_left: Point::default(),
+        size: Size {
+            width: raw.w as u32,
+            height: raw.h as u32,
+        },
+    }
+}
+
+/// Returns the overlapped area of the rectangles, or `None` if they do not overlap.
+fn overlap(a: Rect, b: Rect) -> Option<Rect> {
+    let end = |start: i32, len: u32| i64::from(start) + i64::from(len);
+    let left = a.left().max(b.left());
+    let top = a.top().max(b.top());
+    let right = end(a.left(), a.size.width).min(end(b.left(), b.size.width));
+    let bottom = end(a.top(), a.size.height).min(end(b.top(), b.size.height));
+    (i64::from(left) < right && i64::from(top) < bottom).then(|| Rect {
+        up_left: Point { x: left, y: top },
+        size: Size {
+            width: (right - i64::from(left)) as u32,
+            height: (bottom - i64::from(top)) as u32,
+        },
+    })
+}
+
+/// Clips `dst_area` by the clip area of `dst`, and crops `src_area` in the same ratio. Returns `Ok(None)` if nothing remains.
+///
+/// Returns `Err` if `src_area` or `dst_area` is empty, or `src_area` is out of `src_whole`.
+fn clip_stretch(
+    src_area: Rect,
+    src_whole: Rect,
+    dst_area: Rect,
+    dst_clip: Rect,
+) -> Result<Option<(Rect, Rect)>> {
+    if src_area.is_empty() || dst_area.is_empty() {
+        return Err(SdlError::Others {
+            msg: "stretching areas must not be empty".into(),
+        });
+    }
+    if overlap(src_area, src_whole) != Some(src_area) {
+        return Err(SdlError::Others {
+            msg: "source area must be in the surface".into(),
+        });
+    }
+    let clipped = match overlap(dst_area, dst_clip) {
+        Some(clipped) => clipped,
+        None => return Ok(None),
+    };
+    let scale_x = f64::from(src_area.size.width) / f64::from(dst_area.size.width);
+    let scale_y = f64::from(src_area.size.height) / f64::from(dst_area.size.height);
+    let offset_x = i64::from(clipped.left()) - i64::from(dst_area.left());
+    let offset_y = i64::from(clipped.top()) - i64::from(dst_area.top());
+    let left = (offset_x as f64 * scale_x).round() as u32;
+    let top = (offset_y as f64 * scale_y).round() as u32;
+    let width = (f64::from(clipped.size.width) * scale_x).round() as u32;
+    let height = (f64::from(clipped.size.height) * scale_y).round() as u32;
+    let left = left.min(src_area.size.width - 1);
+    let top = top.min(src_area.size.height - 1);
+    let src = Rect {
+        up_left: src_area.up_left.offset(left as i32, top as i32),
+        size: Size {
+            width: width.clamp(1, src_area.size.width - left),
+            height: height.clamp(1, src_area.size.height - top),
+        },
+    };
+    Ok(Some((src, clipped)))
+}
+
 /// A trait that provides flexible modification methods.
 pub trait Surface {
     /// Returns the raw non-null pointer.
@@ -45,7 +135,11 @@ pub trait Surface {
     }
 
     /// Changes blend mode of the surface.
-    fn blend(self, mode: BlendMode) -> Blended<Self>
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `mode` is [`BlendMode::Custom`], which surfaces do not support, or failed to set the blend mode.
+    fn blend(self, mode: BlendMode) -> Result<Blended<Self>>
     where
         Self: Sized,
     {
@@ -68,6 +162,25 @@ pub trait Surface {
         ColorMod::new(self, color)
     }
 
+    /// Sets the color key of the surface, the pixels of `key` become transparent on copying.
+    fn color_key(self, key: Pixel) -> ColorKey<Self>
+    where
+        Self: Sized,
+    {
+        ColorKey::new(self, key)
+    }
+
+    /// Returns the color key of the surface, or `None` if not set.
+    fn current_color_key(&self) -> Option<Pixel> {
+        let ptr = self.as_ptr().as_ptr();
+        if unsafe { bind::SDL_HasColorKey(ptr) } != bind::SDL_TRUE {
+            return None;
+        }
+        let mut key = 0;
+        let ret = unsafe { bind::SDL_GetColorKey(ptr, &mut key) };
+        (ret == 0).then(|| Pixel::from_u32(key))
+    }
+
     /// Fills in the `area` with the `color`, or whole if `area` is `None`.
     fn fill_rect(&self, area: Option<Rect>, color: Pixel) {
         let area = area.map(Into::into);
@@ -80,7 +193,10 @@ pub trait Surface {
     }
 
     /// Fills in the `areas` with the `color`.
-    fn fill_rects(&self, areas: impl IntoIterator<Item = Rect>, color: Pixel) {
+    fn fill_rects(&self, areas: impl IntoIterator<Item = Rect>, color: Pixel)
+    where
+        Self: Sized,
+    {
         let raw_rects: Vec<_> = areas.into_iter().map(Into::into).collect();
         unsafe {
             let ret = bind::SDL_FillRects(
@@ -103,8 +219,17 @@ pub trait Surface {
         }
     }
 
+    /// Returns the palette of the surface shared with it, or `None` if the surface is not indexed. Changing the colors of the palette affects the surface, such as palette cycling.
+    fn palette(&self) -> Option<Palette> {
+        let format = unsafe { &*self.as_ptr().as_ref().format };
+        NonNull::new(format.palette).map(|palette| unsafe { Palette::from_shared(palette) })
+    }
+
     /// Copies `src_area` area in the surface into `dst_pos` on another surface.
-    fn copy_to<S: Surface>(&self, src_area: Rect, dst: &S, dst_pos: Point) {
+    fn copy_to<S: Surface>(&self, src_area: Rect, dst: &S, dst_pos: Point)
+    where
+        Self: Sized,
+    {
         let src_rect = src_area.into();
         let mut dst_rect = bind::SDL_Rect {
             x: dst_pos.x,
@@ -125,6 +250,144 @@ pub trait Surface {
         }
     }
 
+    /// Copies `src_area` in the surface, or whole if `None`, into `dst_area` on another surface with scaling, or whole if `None`. Returns the final destination area clipped by the clip area of `dst`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the areas are empty, or failed to copy the pixels.
+    fn copy_scaled_to<S: Surface>(
+        &self,
+        src_area: Option<Rect>,
+        dst: &S,
+        dst_area: Option<Rect>,
+    ) -> Result<Rect>
+    where
+        Self: Sized,
+    {
+        if src_area.map_or(false, |area| area.is_empty())
+            || dst_area.map_or(false, |area| area.is_empty())
+        {
+            return Err(SdlError::Others {
+                msg: "scaling areas must not be empty".into(),
+            });
+        }
+        let src_rect = src_area.map(Into::into);
+        let mut dst_rect = dst_area.unwrap_or_else(|| whole_area(dst)).into();
+        let ret = unsafe {
+            bind::SDL_UpperBlitScaled(
+                self.as_ptr().as_ptr(),
+                as_raw(&src_rect),
+                dst.as_ptr().as_ptr(),
+                &mut dst_rect,
+            )
+        };
+        if ret == 0 {
+            Ok(dst_rect.into())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Stretches `src_area` in the surface, or whole if `None`, into `dst_area` on another surface, or whole if `None`. Both surfaces must have the same pixel format, and the blend mode is ignored. Returns the final destination area clipped by the clip area of `dst`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the areas are empty, `src_area` is out of the surface, or the formats are different or unsupported by `filter`.
+    fn stretch_to<S: Surface>(
+        &self,
+        src_area: Option<Rect>,
+        dst: &S,
+        dst_area: Option<Rect>,
+        filter: StretchFilter,
+    ) -> Result<Rect>
+    where
+        Self: Sized,
+    {
+        let src_whole = whole_area(self);
+        let dst_area = dst_area.unwrap_or_else(|| whole_area(dst));
+        let dst_clip = unsafe { dst.as_ptr().as_ref() }.clip_rect.into();
+        let (src_rect, dst_rect) =
+            match clip_stretch(src_area.unwrap_or(src_whole), src_whole, dst_area, dst_clip)? {
+                Some(rects) => rects,
+                None => {
+                    return Ok(Rect {
+                        up_left: dst_area.up_left,
+                        size: Size {
+                            width: 0,
+                            height: 0,
+                        },
+                    })
+                }
+            };
+        let (raw_src, raw_dst) = (src_rect.into(), dst_rect.into());
+        let ret = unsafe {
+            match filter {
+                StretchFilter::Nearest => bind::SDL_SoftStretch(
+                    self.as_ptr().as_ptr(),
+                    &raw_src,
+                    dst.as_ptr().as_ptr(),
+                    &raw_dst,
+                ),
+                StretchFilter::Linear => bind::SDL_SoftStretchLinear(
+                    self.as_ptr().as_ptr(),
+                    &raw_src,
+                    dst.as_ptr().as_ptr(),
+                    &raw_dst,
+                ),
+            }
+        };
+        if ret == 0 {
+            Ok(dst_rect)
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Converts the surface into a new surface with the pixel format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface, or the conversion is unsupported.
+    fn convert(&self, format: &PixelFormat) -> Result<Owned> {
+        let ptr = unsafe { bind::SDL_ConvertSurface(self.as_ptr().as_ptr(), format.as_ptr(), 0) };
+        NonNull::new(ptr).map_or_else(
+            || Err(SdlError::Others { msg: Sdl::error() }),
+            |raw| Ok(Owned::from_raw(raw)),
+        )
+    }
+
+    /// Converts the surface into a new surface with the pixel format kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface, or the conversion is unsupported.
+    fn convert_to(&self, kind: PixelFormatKind) -> Result<Owned> {
+        let ptr =
+            unsafe { bind::SDL_ConvertSurfaceFormat(self.as_ptr().as_ptr(), kind.as_raw(), 0) };
+        NonNull::new(ptr).map_or_else(
+            || Err(SdlError::Others { msg: Sdl::error() }),
+            |raw| Ok(Owned::from_raw(raw)),
+        )
+    }
+
+    /// Locks the surface if needed, to read/write the pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to lock the surface.
+    fn lock(&mut self) -> Result<Lock> {
+        Lock::new(self)
+    }
+
+    /// Locks the surface, and returns the canvas to draw onto it in software.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to lock the surface.
+    fn canvas(&mut self) -> Result<Canvas> {
+        Canvas::new(self)
+    }
+
     /// Run-length encodes the surface.
     fn rle(&'_ mut self) -> Rle<'_, Self>
     where
@@ -133,3 +396,80 @@ pub trait Surface {
         Rle::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rect {
+        Rect {
+            up_left: Point { x, y },
+            size: Size { width, height },
+        }
+    }
+
+    #[test]
+    fn clip_stretch_inside() {
+        let whole = rect(0, 0, 10, 10);
+        let dst_clip = rect(0, 0, 100, 100);
+        let clipped = clip_stretch(whole, whole, rect(10, 20, 40, 40), dst_clip).unwrap();
+        assert_eq!(clipped, Some((whole, rect(10, 20, 40, 40))));
+    }
+
+    #[test]
+    fn clip_stretch_crops_source_by_ratio() {
+        let whole = rect(0, 0, 10, 10);
+        let dst_clip = rect(0, 0, 100, 100);
+        let clipped = clip_stretch(whole, whole, rect(-10, -10, 20, 20), dst_clip).unwrap();
+        assert_eq!(clipped, Some((rect(5, 5, 5, 5), rect(0, 0, 10, 10))));
+        let clipped = clip_stretch(whole, whole, rect(90, 95, 20, 20), dst_clip).unwrap();
+        assert_eq!(clipped, Some((rect(0, 0, 5, 3), rect(90, 95, 10, 5))));
+    }
+
+    #[test]
+    fn clip_stretch_keeps_a_source_pixel() {
+        let whole = rect(0, 0, 2, 2);
+        let dst_clip = rect(0, 0, 100, 100);
+        let clipped = clip_stretch(whole, whole, rect(-99, -99, 100, 100), dst_clip).unwrap();
+        assert_eq!(clipped, Some((rect(1, 1, 1, 1), rect(0, 0, 1, 1))));
+    }
+
+    #[test]
+    fn clip_stretch_off_surface() {
+        let whole = rect(0, 0, 10, 10);
+        let dst_clip = rect(0, 0, 100, 100);
+        assert_eq!(
+            clip_stretch(whole, whole, rect(100, 0, 10, 10), dst_clip).unwrap(),
+            None
+        );
+        assert_eq!(
+            clip_stretch(whole, whole, rect(-20, -20, 10, 10), dst_clip).unwrap(),
+            None
+        );
+        assert_eq!(
+            clip_stretch(whole, whole, rect(i32::MAX, 0, u32::MAX, 10), dst_clip).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn clip_stretch_rejects_degenerate() {
+        let whole = rect(0, 0, 10, 10);
+        let dst_clip = rect(0, 0, 100, 100);
+        assert!(clip_stretch(rect(0, 0, 0, 10), whole, rect(0, 0, 10, 10), dst_clip).is_err());
+        assert!(clip_stretch(whole, whole, rect(0, 0, 10, 0), dst_clip).is_err());
+        assert!(clip_stretch(rect(-1, 0, 10, 10), whole, rect(0, 0, 10, 10), dst_clip).is_err());
+        assert!(clip_stretch(rect(5, 5, 10, 10), whole, rect(0, 0, 10, 10), dst_clip).is_err());
+        assert!(clip_stretch(rect(20, 20, 5, 5), whole, rect(0, 0, 10, 10), dst_clip).is_err());
+    }
+
+    #[test]
+    fn overlap_rects() {
+        assert_eq!(
+            overlap(rect(0, 0, 10, 10), rect(5, -5, 10, 10)),
+            Some(rect(5, 0, 5, 5))
+        );
+        assert_eq!(overlap(rect(0, 0, 10, 10), rect(10, 0, 10, 10)), None);
+        assert_eq!(overlap(rect(0, 0, 0, 10), rect(0, 0, 10, 10)), None);
+    }
+}
@@ -1,7 +1,7 @@
 //! Blending for a [`Surface`].
 
 use crate::color::BlendMode;
-use crate::{bind, Sdl};
+use crate::{bind, Result, Sdl, SdlError};
 
 use super::{RawSurface, Surface};
 
@@ -20,15 +20,15 @@ impl<S> Blended<S> {
 }
 
 impl<S: Surface> Blended<S> {
-    pub(super) fn new(surface: S, mode: BlendMode) -> Self {
-        let raw_mode = mode.into();
-        unsafe {
-            let ret = bind::SDL_SetSurfaceBlendMode(surface.as_ptr().as_ptr(), raw_mode);
-            if ret != 0 {
-                Sdl::error_then_panic("Setting surface blend mode");
-            }
+    pub(super) fn new(surface: S, mode: BlendMode) -> Result<Self> {
+        if let BlendMode::Custom(_) = mode {
+            return Err(SdlError::UnsupportedFeature);
         }
-        Self { surface, mode }
+        let ret = unsafe { bind::SDL_SetSurfaceBlendMode(surface.as_ptr().as_ptr(), mode.into()) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+        Ok(Self { surface, mode })
     }
 }
 
@@ -4,7 +4,7 @@ use static_assertions::assert_not_impl_all;
 use std::ffi::{CStr, CString};
 use std::ptr::NonNull;
 
-use crate::{bind, Result, Sdl, SdlError};
+use crate::{bind, file::RwOps, Result, Sdl, SdlError};
 
 use super::{RawSurface, Surface};
 
@@ -43,6 +43,26 @@ impl Bmp {
         .ok_or_else(|| SdlError::Others { msg: Sdl::error() })?;
         Ok(Self { ptr })
     }
+
+    /// Constructs from the bitmap data read from `src`. `src` is not closed, and its position is advanced past the bitmap.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to read the data, uses an unknown data format, or is corrupt.
+    pub fn from_rw(src: &mut RwOps) -> Result<Self> {
+        let ptr = NonNull::new(unsafe { bind::SDL_LoadBMP_RW(src.ptr().as_ptr(), 0) })
+            .ok_or_else(|| SdlError::Others { msg: Sdl::error() })?;
+        Ok(Self { ptr })
+    }
+
+    /// Constructs from the bitmap data in memory, such as embedded by `include_bytes!`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the data uses an unknown data format, or is corrupt.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_rw(&mut RwOps::from_mem(bytes)?)
+    }
 }
 
 impl Surface for Bmp {
@@ -69,6 +89,13 @@ pub trait BmpSaveExt {
     ///
     /// Returns `Err` if failed to save an image to the file.
     fn save_bmp(&self, file_name: &str) -> std::result::Result<(), BmpSaveError>;
+
+    /// Saves the surface image as BMP format into `dst`. `dst` is not closed, and its position is advanced past the written image.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to write an image into `dst`.
+    fn save_bmp_to(&self, dst: &mut RwOps) -> std::result::Result<(), BmpSaveError>;
 }
 
 impl<T: Surface> BmpSaveExt for T {
@@ -87,4 +114,12 @@ impl<T: Surface> BmpSaveExt for T {
         }
         Ok(())
     }
+
+    fn save_bmp_to(&self, dst: &mut RwOps) -> std::result::Result<(), BmpSaveError> {
+        let ret = unsafe { bind::SDL_SaveBMP_RW(self.as_ptr().as_ptr(), dst.ptr().as_ptr(), 0) };
+        if ret != 0 {
+            return Err(BmpSaveError(Sdl::error()));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,50 @@
+//! Borrowed surface, created over the pixels provided by the caller.
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use super::{owned::create_from, RawSurface, Surface};
+use crate::{bind, color::pixel::kind::PixelFormatKind, geo::Size, Result};
+
+/// A [`Surface`] over the borrowed pixels, without copying them. The pixels cannot be touched while the surface is alive.
+#[derive(Debug)]
+pub struct Borrowed<'pixels> {
+    raw: NonNull<RawSurface>,
+    _phantom: PhantomData<&'pixels mut [u8]>,
+}
+
+impl<'pixels> Borrowed<'pixels> {
+    /// Creates a new surface over the pixels with the pitch and the pixel format kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to create the surface, or the format is unsupported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pitch` is shorter than a row, or `pixels` is shorter than the rows of the height.
+    pub fn new(
+        pixels: &'pixels mut [u8],
+        size: Size,
+        pitch: usize,
+        kind: PixelFormatKind,
+    ) -> Result<Self> {
+        let raw = unsafe { create_from(pixels, size, pitch, kind)? };
+        Ok(Self {
+            raw,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl Drop for Borrowed<'_> {
+    fn drop(&mut self) {
+        unsafe { bind::SDL_FreeSurface(self.raw.as_ptr()) }
+    }
+}
+
+impl Surface for Borrowed<'_> {
+    fn as_ptr(&self) -> NonNull<RawSurface> {
+        self.raw
+    }
+}
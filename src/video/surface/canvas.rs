@@ -0,0 +1,417 @@
+//! Drawing geometries onto a [`Surface`] in software, without a renderer.
+
+use crate::color::{BlendMode, Rgba};
+use crate::geo::{Line, Point, Rect};
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::{lock::Lock, Surface};
+
+/// A canvas draws geometries onto the surface in software, with the same vocabulary as [`Pen`](crate::renderer::pen::Pen).
+///
+/// The drawings are clipped by the clip area of the surface, see [`Surface::clipped`]. The colors are blended by the blend mode of the surface at first, and it can be changed by [`Canvas::set_blend_mode`]. [`BlendMode::Custom`] is treated as [`BlendMode::AlphaBlend`].
+///
+/// The pixel format of the surface must be a packed or indexed format of bytes per pixel.
+#[derive(Debug)]
+pub struct Canvas<'surface> {
+    lock: Lock<'surface>,
+    clip: Rect,
+    color: Rgba,
+    blend_mode: BlendMode,
+}
+
+impl<'surface> Canvas<'surface> {
+    pub(super) fn new<S: Surface + ?Sized>(surface: &'surface mut S) -> Result<Self> {
+        let ptr = surface.as_ptr();
+        let clip = unsafe { ptr.as_ref() }.clip_rect.into();
+        let mut raw_mode = bind::SDL_BLENDMODE_NONE;
+        let ret = unsafe { bind::SDL_GetSurfaceBlendMode(ptr.as_ptr(), &mut raw_mode) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+        Ok(Self {
+            lock: Lock::new(surface)?,
+            clip,
+            color: Rgba {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            blend_mode: raw_mode.into(),
+        })
+    }
+
+    /// Returns the lock of the surface, to read the drawn pixels.
+    #[must_use]
+    pub fn lock(&self) -> &Lock<'surface> {
+        &self.lock
+    }
+
+    /// Returns the area where the drawings are clipped.
+    pub fn clip(&self) -> Rect {
+        self.clip
+    }
+
+    /// Sets the drawing color.
+    pub fn set_color(&mut self, color: Rgba) {
+        self.color = color;
+    }
+
+    /// Returns the drawing color.
+    pub fn color(&self) -> Rgba {
+        self.color
+    }
+
+    /// Returns the current color blend mode.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Sets the color blend mode.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    /// Clears the clip area with the color, without blending.
+    pub fn clear(&mut self) {
+        let mode = std::mem::replace(&mut self.blend_mode, BlendMode::None);
+        self.fill_rect(self.clip);
+        self.blend_mode = mode;
+    }
+
+    /// Draw the point.
+    pub fn point(&mut self, point: Point) {
+        self.plot(point);
+    }
+
+    /// Draws the points.
+    pub fn points(&mut self, points: impl IntoIterator<Item = Point>) {
+        self.plot_unique(points.into_iter().collect());
+    }
+
+    /// Draws the line.
+    pub fn line(&mut self, line: Line) {
+        let mut points = vec![];
+        line_points(line, &mut points);
+        self.plot_unique(points);
+    }
+
+    /// Draws the lines.
+    pub fn lines(&mut self, points: impl IntoIterator<Item = Point>) {
+        let vertices: Vec<_> = points.into_iter().collect();
+        let mut points = vec![];
+        for pair in vertices.windows(2) {
+            line_points(
+                Line {
+                    start: pair[0],
+                    end: pair[1],
+                },
+                &mut points,
+            );
+        }
+        self.plot_unique(points);
+    }
+
+    /// Draws the border of the rectangle.
+    pub fn stroke_rect(&mut self, rect: Rect) {
+        self.stroke_rects(std::iter::once(rect));
+    }
+
+    /// Draws the borders of the rectangles.
+    pub fn stroke_rects(&mut self, rects: impl IntoIterator<Item = Rect>) {
+        let mut points = vec![];
+        for rect in rects {
+            if rect.is_empty() {
+                continue;
+            }
+            let (left, top) = (rect.left(), rect.top());
+            let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+            let corners = [
+                Point { x: left, y: top },
+                Point { x: right, y: top },
+                Point {
+                    x: right,
+                    y: bottom,
+                },
+                Point { x: left, y: bottom },
+                Point { x: left, y: top },
+            ];
+            for pair in corners.windows(2) {
+                line_points(
+                    Line {
+                        start: pair[0],
+                        end: pair[1],
+                    },
+                    &mut points,
+                );
+            }
+        }
+        self.plot_unique(points);
+    }
+
+    /// Fills the rectangle.
+    pub fn fill_rect(&mut self, rect: Rect) {
+        let rect = match rect.intersect(self.clip) {
+            Some(rect) => rect,
+            None => return,
+        };
+        for y in rect.top()..rect.bottom() {
+            self.span(rect.left(), rect.right(), y);
+        }
+    }
+
+    /// Fills the rectangles.
+    pub fn fill_rects(&mut self, rects: impl IntoIterator<Item = Rect>) {
+        for rect in rects {
+            self.fill_rect(rect);
+        }
+    }
+
+    /// Draws the outline of the circle.
+    pub fn stroke_circle(&mut self, center: Point, radius: u32) {
+        let radius = i64::from(radius);
+        for (dy, y) in self.circle_rows(center, radius) {
+            let outer = circle_half_width(radius, dy);
+            // Connects to the next row outward, so the outline has no gaps.
+            let inner = if dy.abs() < radius {
+                (circle_half_width(radius, dy.abs() + 1) + 1).min(outer)
+            } else {
+                0
+            };
+            if inner == 0 {
+                self.span_around(center.x, -outer, outer + 1, y);
+            } else {
+                self.span_around(center.x, -outer, -inner + 1, y);
+                self.span_around(center.x, inner, outer + 1, y);
+            }
+        }
+    }
+
+    /// Fills the circle.
+    pub fn fill_circle(&mut self, center: Point, radius: u32) {
+        let radius = i64::from(radius);
+        for (dy, y) in self.circle_rows(center, radius) {
+            let half_width = circle_half_width(radius, dy);
+            self.span_around(center.x, -half_width, half_width + 1, y);
+        }
+    }
+
+    /// Draws the outline of the polygon, connecting the last vertex to the first one.
+    pub fn stroke_polygon(&mut self, vertices: &[Point]) {
+        let mut points = vec![];
+        for (i, &start) in vertices.iter().enumerate() {
+            let end = vertices[(i + 1) % vertices.len()];
+            line_points(Line { start, end }, &mut points);
+        }
+        self.plot_unique(points);
+    }
+
+    /// Fills the polygon by the even-odd rule. The pixels whose center is inside the polygon are filled.
+    pub fn fill_polygon(&mut self, vertices: &[Point]) {
+        if vertices.len() < 3 {
+            return;
+        }
+        let top = vertices
+            .iter()
+            .map(|p| p.y)
+            .min()
+            .unwrap()
+            .max(self.clip.top());
+        let bottom = vertices
+            .iter()
+            .map(|p| p.y)
+            .max()
+            .unwrap()
+            .min(self.clip.bottom());
+        let mut crossings = vec![];
+        for y in top..bottom {
+            let center_y = f64::from(y) + 0.5;
+            crossings.clear();
+            for (i, start) in vertices.iter().enumerate() {
+                let end = vertices[(i + 1) % vertices.len()];
+                let (y0, y1) = (f64::from(start.y), f64::from(end.y));
+                if (y0 <= center_y) == (y1 <= center_y) {
+                    continue;
+                }
+                let t = (center_y - y0) / (y1 - y0);
+                crossings.push(f64::from(start.x) + t * f64::from(end.x - start.x));
+            }
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            for pair in crossings.chunks_exact(2) {
+                let left = (pair[0] - 0.5).ceil() as i32;
+                let right = (pair[1] - 0.5).ceil() as i32;
+                self.span(left, right, y);
+            }
+        }
+    }
+
+    /// Fills the area connected to `seed` in the same color, up, down, left and right. The area is limited in the clip area.
+    pub fn flood_fill(&mut self, seed: Point) {
+        let target = match self.pixel_in_clip(seed) {
+            Some(color) => color,
+            None => return,
+        };
+        let Rect { up_left, size } = self.clip;
+        let index_of = |Point { x, y }: Point| {
+            (y - up_left.y) as usize * size.width as usize + (x - up_left.x) as usize
+        };
+        let mut visited = vec![false; size.width as usize * size.height as usize];
+        let mut region = vec![];
+        let mut stack = vec![seed];
+        visited[index_of(seed)] = true;
+        while let Some(point) = stack.pop() {
+            region.push(point);
+            for neighbor in [
+                point.offset(1, 0),
+                point.offset(-1, 0),
+                point.offset(0, 1),
+                point.offset(0, -1),
+            ] {
+                if self.pixel_in_clip(neighbor) != Some(target) || visited[index_of(neighbor)] {
+                    continue;
+                }
+                visited[index_of(neighbor)] = true;
+                stack.push(neighbor);
+            }
+        }
+        for point in region {
+            self.plot(point);
+        }
+    }
+
+    fn pixel_in_clip(&self, point: Point) -> Option<Rgba> {
+        if !self.in_clip(point) {
+            return None;
+        }
+        self.lock.get_pixel(point)
+    }
+
+    fn in_clip(&self, Point { x, y }: Point) -> bool {
+        self.clip.left() <= x
+            && x < self.clip.right()
+            && self.clip.top() <= y
+            && y < self.clip.bottom()
+    }
+
+    /// Draws the pixels from `left` to `right` exclusive on the row `y`.
+    fn span(&mut self, left: i32, right: i32, y: i32) {
+        if y < self.clip.top() || self.clip.bottom() <= y {
+            return;
+        }
+        for x in left.max(self.clip.left())..right.min(self.clip.right()) {
+            self.plot(Point { x, y });
+        }
+    }
+
+    /// Returns the offsets from the center and the rows of the circle, only in the clip area.
+    fn circle_rows(&self, center: Point, radius: i64) -> impl Iterator<Item = (i64, i32)> {
+        let center_y = i64::from(center.y);
+        let top = (-radius).max(i64::from(self.clip.top()) - center_y);
+        let bottom = radius.min(i64::from(self.clip.bottom()) - 1 - center_y);
+        (top..=bottom).map(move |dy| (dy, (center_y + dy) as i32))
+    }
+
+    /// Draws the pixels from `center_x + left` to `center_x + right` exclusive on the row `y`, where the ends may be out of `i32`.
+    fn span_around(&mut self, center_x: i32, left: i64, right: i64, y: i32) {
+        let (min, max) = (i64::from(self.clip.left()), i64::from(self.clip.right()));
+        let clamp = |x: i64| (i64::from(center_x) + x).clamp(min, max) as i32;
+        self.span(clamp(left), clamp(right), y);
+    }
+
+    /// Draws the points once for each, not to blend twice on the overlaps.
+    fn plot_unique(&mut self, mut points: Vec<Point>) {
+        points.sort_unstable_by_key(|p| (p.y, p.x));
+        points.dedup();
+        for point in points {
+            self.plot(point);
+        }
+    }
+
+    fn plot(&mut self, point: Point) {
+        if !self.in_clip(point) {
+            return;
+        }
+        let src = self.color;
+        let color = match self.blend_mode {
+            BlendMode::None => src,
+            mode => {
+                let dst = match self.lock.get_pixel(point) {
+                    Some(dst) => dst,
+                    None => return,
+                };
+                blend(src, dst, mode)
+            }
+        };
+        self.lock.set_pixel(point, color);
+    }
+}
+
+/// Blends `src` onto `dst` in the same equations as SDL2.
+fn blend(src: Rgba, dst: Rgba, mode: BlendMode) -> Rgba {
+    let alpha = u32::from(src.a);
+    let mix =
+        |s: u8, d: u8| ((u32::from(s) * alpha + u32::from(d) * (255 - alpha) + 127) / 255) as u8;
+    let add = |s: u8, d: u8| (u32::from(d) + (u32::from(s) * alpha + 127) / 255).min(255) as u8;
+    let mul = |s: u8, d: u8| ((u32::from(s) * u32::from(d) + 127) / 255) as u8;
+    match mode {
+        BlendMode::None => src,
+        BlendMode::Add => Rgba {
+            r: add(src.r, dst.r),
+            g: add(src.g, dst.g),
+            b: add(src.b, dst.b),
+            a: dst.a,
+        },
+        BlendMode::Mul => Rgba {
+            r: mul(src.r, dst.r),
+            g: mul(src.g, dst.g),
+            b: mul(src.b, dst.b),
+            a: dst.a,
+        },
+        BlendMode::Multiply => {
+            let multiply = |s: u8, d: u8| mul(s, d).saturating_add(mix(0, d));
+            Rgba {
+                r: multiply(src.r, dst.r),
+                g: multiply(src.g, dst.g),
+                b: multiply(src.b, dst.b),
+                a: dst.a,
+            }
+        }
+        _ => Rgba {
+            r: mix(src.r, dst.r),
+            g: mix(src.g, dst.g),
+            b: mix(src.b, dst.b),
+            a: (alpha + (u32::from(dst.a) * (255 - alpha) + 127) / 255) as u8,
+        },
+    }
+}
+
+/// Returns the half width of the circle in `radius` on the row `dy` apart from the center.
+fn circle_half_width(radius: i64, dy: i64) -> i64 {
+    let dy = dy.abs();
+    ((radius - dy) as f64 * (radius + dy) as f64).sqrt().round() as i64
+}
+
+/// Appends the points on the line by Bresenham's algorithm, including the both ends.
+fn line_points(Line { start, end }: Line, points: &mut Vec<Point>) {
+    let (dx, dy) = ((end.x - start.x).abs(), -(end.y - start.y).abs());
+    let (step_x, step_y) = (
+        if start.x < end.x { 1 } else { -1 },
+        if start.y < end.y { 1 } else { -1 },
+    );
+    let (mut x, mut y, mut error) = (start.x, start.y, dx + dy);
+    loop {
+        points.push(Point { x, y });
+        if x == end.x && y == end.y {
+            break;
+        }
+        let doubled = 2 * error;
+        if dy <= doubled {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Color key for a [`Surface`].
+
+use crate::color::pixel::Pixel;
+use crate::{bind, Sdl};
+
+use super::{RawSurface, Surface};
+
+/// A [`Surface`] with the color key, the pixels of the key are transparent on copying.
+#[derive(Debug)]
+pub struct ColorKey<S> {
+    surface: S,
+    key: Pixel,
+}
+
+impl<S> ColorKey<S> {
+    /// Returns the color key.
+    pub fn key(&self) -> Pixel {
+        self.key
+    }
+}
+
+impl<S: Surface> ColorKey<S> {
+    pub(super) fn new(surface: S, key: Pixel) -> Self {
+        unsafe {
+            let ret =
+                bind::SDL_SetColorKey(surface.as_ptr().as_ptr(), bind::SDL_TRUE as _, key.as_u32());
+            if ret != 0 {
+                Sdl::error_then_panic("Setting surface color key");
+            }
+        }
+        Self { surface, key }
+    }
+
+    /// Removes the color key, and returns the inner surface.
+    pub fn into_inner(self) -> S {
+        unsafe {
+            let ret = bind::SDL_SetColorKey(
+                self.surface.as_ptr().as_ptr(),
+                bind::SDL_FALSE as _,
+                self.key.as_u32(),
+            );
+            if ret != 0 {
+                Sdl::error_then_panic("Removing surface color key");
+            }
+        }
+        self.surface
+    }
+}
+
+impl<S: Surface> Surface for ColorKey<S> {
+    fn as_ptr(&self) -> std::ptr::NonNull<RawSurface> {
+        self.surface.as_ptr()
+    }
+}
@@ -0,0 +1,310 @@
+//! Comparing [`Surface`]s pixel by pixel, for visual regression tests.
+
+use typed_builder::TypedBuilder;
+
+use crate::color::pixel::{
+    kind::PixelFormatKind, layout::PackedPixelLayout, order::PackedPixelOrder, ty::PackedPixelType,
+};
+use crate::color::Rgba;
+use crate::geo::{Point, Rect, Size};
+use crate::{Result, SdlError};
+
+use super::{owned::Owned, Surface};
+
+/// The format both surfaces are normalized into before comparing.
+const COMPARE_FORMAT: PixelFormatKind = PixelFormatKind::Packed {
+    ty: PackedPixelType::_32,
+    order: PackedPixelOrder::Argb,
+    layout: PackedPixelLayout::_8888,
+};
+
+const MISMATCH_COLOR: u32 = 0xff_ff_00_00;
+
+/// Options on comparing surfaces by [`compare`].
+#[derive(Clone, TypedBuilder)]
+pub struct CompareOptions<'mask> {
+    /// The maximum difference allowed in each channel. Defaults to no difference.
+    #[builder(default = Rgba { r: 0, g: 0, b: 0, a: 0 })]
+    pub tolerance: Rgba,
+    /// The mask in the same size as the compared surfaces. The pixels where the mask is not black are ignored.
+    #[builder(default, setter(strip_option))]
+    pub ignore_mask: Option<&'mask dyn Surface>,
+}
+
+impl std::fmt::Debug for CompareOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompareOptions")
+            .field("tolerance", &self.tolerance)
+            .field("ignore_mask", &self.ignore_mask.map(Surface::as_ptr))
+            .finish()
+    }
+}
+
+/// A state of a pixel in [`Comparison`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PixelState {
+    /// The pixels are the same within the tolerance.
+    Matched,
+    /// The pixels are different over the tolerance.
+    Mismatched,
+    /// The pixel is ignored by the mask.
+    Ignored,
+}
+
+/// A result of [`compare`], reporting where the surfaces differ.
+#[derive(Clone)]
+pub struct Comparison {
+    size: Size,
+    actual: Vec<u32>,
+    states: Vec<PixelState>,
+    mismatched: usize,
+    ignored: usize,
+    max_difference: Rgba,
+}
+
+impl std::fmt::Debug for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Comparison")
+            .field("size", &self.size)
+            .field("mismatched", &self.mismatched)
+            .field("ignored", &self.ignored)
+            .field("max_difference", &self.max_difference)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compares `actual` with `expected` pixel by pixel. Both surfaces are converted into the same 32-bit format before comparing, so they can have the different formats.
+///
+/// # Errors
+///
+/// Returns `Err` if the sizes of the surfaces or the mask are different, or failed to convert the surfaces.
+pub fn compare<E: Surface, A: Surface>(
+    expected: &E,
+    actual: &A,
+    options: &CompareOptions,
+) -> Result<Comparison> {
+    let (size, expected) = read_pixels(expected)?;
+    let (actual_size, actual) = read_pixels(actual)?;
+    if size != actual_size {
+        return Err(SdlError::Others {
+            msg: "sizes of the surfaces must be the same".into(),
+        });
+    }
+    let mask = match options.ignore_mask {
+        Some(mask) => {
+            let (mask_size, mask) = read_pixels(mask)?;
+            if size != mask_size {
+                return Err(SdlError::Others {
+                    msg: "size of the ignore mask must be the same as the surfaces".into(),
+                });
+            }
+            Some(mask)
+        }
+        None => None,
+    };
+
+    let tolerance = options.tolerance;
+    let mut max_difference = Rgba {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+    let (mut mismatched, mut ignored) = (0, 0);
+    let states = expected
+        .iter()
+        .zip(&actual)
+        .enumerate()
+        .map(|(i, (&expected, &actual))| {
+            if mask
+                .as_ref()
+                .map_or(false, |mask| mask[i] & 0xff_ff_ff != 0)
+            {
+                ignored += 1;
+                return PixelState::Ignored;
+            }
+            let diff = difference(expected, actual);
+            max_difference = Rgba {
+                r: max_difference.r.max(diff.r),
+                g: max_difference.g.max(diff.g),
+                b: max_difference.b.max(diff.b),
+                a: max_difference.a.max(diff.a),
+            };
+            if tolerance.r < diff.r
+                || tolerance.g < diff.g
+                || tolerance.b < diff.b
+                || tolerance.a < diff.a
+            {
+                mismatched += 1;
+                PixelState::Mismatched
+            } else {
+                PixelState::Matched
+            }
+        })
+        .collect();
+    Ok(Comparison {
+        size,
+        actual,
+        states,
+        mismatched,
+        ignored,
+        max_difference,
+    })
+}
+
+/// Reads the pixels of the surface in [`COMPARE_FORMAT`] without the row padding.
+fn read_pixels<S: Surface + ?Sized>(surface: &S) -> Result<(Size, Vec<u32>)> {
+    let mut converted = surface.convert_to(COMPARE_FORMAT)?;
+    let lock = converted.lock()?;
+    let size = lock.size();
+    let width = size.width as usize;
+    let pixels = lock
+        .rows()
+        .flat_map(|row| {
+            row[..width * 4]
+                .chunks(4)
+                .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        })
+        .collect();
+    Ok((size, pixels))
+}
+
+/// Returns the absolute difference of each channel of the pixels in [`COMPARE_FORMAT`].
+fn difference(expected: u32, actual: u32) -> Rgba {
+    let channel = |shift: u32| {
+        let (expected, actual) = ((expected >> shift) as u8, (actual >> shift) as u8);
+        expected.max(actual) - expected.min(actual)
+    };
+    Rgba {
+        r: channel(16),
+        g: channel(8),
+        b: channel(0),
+        a: channel(24),
+    }
+}
+
+impl Comparison {
+    /// Returns the size of the compared surfaces.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns whether no pixels are mismatched.
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.mismatched == 0
+    }
+
+    /// Returns the number of the mismatched pixels.
+    #[must_use]
+    pub fn mismatched_count(&self) -> usize {
+        self.mismatched
+    }
+
+    /// Returns the number of the pixels ignored by the mask.
+    #[must_use]
+    pub fn ignored_count(&self) -> usize {
+        self.ignored
+    }
+
+    /// Returns the maximum difference of each channel in the pixels not ignored.
+    pub fn max_difference(&self) -> Rgba {
+        self.max_difference
+    }
+
+    /// Returns the state of the pixel at `pos`, or `None` if `pos` is out of the surfaces.
+    #[must_use]
+    pub fn state(&self, pos: Point) -> Option<PixelState> {
+        self.index_of(pos).map(|index| self.states[index])
+    }
+
+    /// Returns the bounding box of all the mismatched pixels, or `None` if matched.
+    #[must_use]
+    pub fn bounds(&self) -> Option<Rect> {
+        self.regions().into_iter().reduce(Rect::union)
+    }
+
+    /// Returns the bounding boxes of the mismatched regions, the mismatched pixels adjacent to each other including diagonals.
+    #[must_use]
+    pub fn regions(&self) -> Vec<Rect> {
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        let mut visited = vec![false; self.states.len()];
+        let mut regions = vec![];
+        let mut stack = vec![];
+        for start in 0..self.states.len() {
+            if visited[start] || self.states[start] != PixelState::Mismatched {
+                continue;
+            }
+            visited[start] = true;
+            stack.push(start);
+            let (mut xs, mut ys) = ([i32::MAX, i32::MIN], [i32::MAX, i32::MIN]);
+            while let Some(index) = stack.pop() {
+                let (x, y) = (
+                    (index % width as usize) as i32,
+                    (index / width as usize) as i32,
+                );
+                xs = [xs[0].min(x), xs[1].max(x + 1)];
+                ys = [ys[0].min(y), ys[1].max(y + 1)];
+                for (dx, dy) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy))) {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || width <= nx || ny < 0 || height <= ny {
+                        continue;
+                    }
+                    let neighbor = (ny * width + nx) as usize;
+                    if !visited[neighbor] && self.states[neighbor] == PixelState::Mismatched {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            regions.push(Rect::from_xs_ys(xs, ys));
+        }
+        regions
+    }
+
+    /// Creates the surface highlighting the differences. The mismatched pixels are red, the ignored pixels are dark blue, and the matched pixels are the actual surface in faded gray.
+    ///
+    /// The result can be saved by [`BmpSaveExt::save_bmp`](super::bmp::BmpSaveExt::save_bmp).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface.
+    pub fn diff_surface(&self) -> Result<Owned> {
+        let mut diff = Owned::with_kind(self.size, COMPARE_FORMAT)?;
+        {
+            let mut lock = diff.lock()?;
+            let width = self.size.width as usize;
+            for (y, row) in lock.rows_mut().enumerate() {
+                for (x, dst) in row[..width * 4].chunks_mut(4).enumerate() {
+                    let index = y * width + x;
+                    let pixel = match self.states[index] {
+                        PixelState::Mismatched => MISMATCH_COLOR,
+                        PixelState::Ignored => {
+                            let gray = faded_gray(self.actual[index]) / 2;
+                            0xff_00_00_00 | (gray << 16) | (gray << 8) | (0x80 + gray)
+                        }
+                        PixelState::Matched => {
+                            let gray = faded_gray(self.actual[index]);
+                            0xff_00_00_00 | (gray << 16) | (gray << 8) | gray
+                        }
+                    };
+                    dst.copy_from_slice(&pixel.to_ne_bytes());
+                }
+            }
+        }
+        Ok(diff)
+    }
+
+    fn index_of(&self, Point { x, y }: Point) -> Option<usize> {
+        let in_surface =
+            0 <= x && (x as u32) < self.size.width && 0 <= y && (y as u32) < self.size.height;
+        in_surface.then(|| y as usize * self.size.width as usize + x as usize)
+    }
+}
+
+/// Returns the luminance of the pixel in [`COMPARE_FORMAT`], faded into the upper half so that red stands out.
+fn faded_gray(pixel: u32) -> u32 {
+    let (r, g, b) = ((pixel >> 16) & 0xff, (pixel >> 8) & 0xff, pixel & 0xff);
+    let luma = (r * 299 + g * 587 + b * 114) / 1000;
+    0x80 + luma / 2
+}
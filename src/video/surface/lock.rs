@@ -0,0 +1,280 @@
+//! Locking the surface for reading/writing the pixels.
+
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr::NonNull;
+
+use crate::color::pixel::{kind::PixelFormatKind, ty::BitmapPixelType, Pixel, PixelFormat};
+use crate::color::Rgba;
+use crate::geo::{Point, Size};
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::{RawSurface, Surface};
+
+/// A lock of the surface, ready to read/write as the raw pixels. The surface is locked only if it needs, such as RLE accelerated.
+///
+/// The pixels are arranged in rows of [`Lock::pitch`] bytes, which may be longer than the width in bytes.
+pub struct Lock<'surface> {
+    surface: NonNull<RawSurface>,
+    locked: bool,
+    format: ManuallyDrop<PixelFormat>,
+    _phantom: PhantomData<&'surface mut ()>,
+}
+
+impl std::fmt::Debug for Lock<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lock")
+            .field("size", &self.size())
+            .field("pitch", &self.pitch())
+            .field("format", &*self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'surface> Lock<'surface> {
+    pub(super) fn new<S: Surface + ?Sized>(surface: &'surface mut S) -> Result<Self> {
+        Self::from_ptr(surface.as_ptr())
+    }
+
+    /// Locks the surface only to read the pixels, so the pixels must not be written through this.
+    pub(super) fn read<S: Surface + ?Sized>(surface: &'surface S) -> Result<Self> {
+        Self::from_ptr(surface.as_ptr())
+    }
+
+    fn from_ptr(surface: NonNull<RawSurface>) -> Result<Self> {
+        let raw = unsafe { surface.as_ref() };
+        let locked = raw.flags & bind::SDL_RLEACCEL != 0;
+        if locked {
+            let ret = unsafe { bind::SDL_LockSurface(surface.as_ptr()) };
+            if ret != 0 {
+                return Err(SdlError::Others { msg: Sdl::error() });
+            }
+        }
+        let format = unsafe { PixelFormat::borrowed(NonNull::new(raw.format).unwrap()) };
+        Ok(Self {
+            surface,
+            locked,
+            format,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn raw(&self) -> &RawSurface {
+        unsafe { self.surface.as_ref() }
+    }
+
+    /// Returns the width of the surface.
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.raw().w as u32
+    }
+
+    /// Returns the height of the surface.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.raw().h as u32
+    }
+
+    /// Returns the size of the surface.
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.width(),
+            height: self.height(),
+        }
+    }
+
+    /// Returns the number of bytes in a row of the pixels.
+    #[must_use]
+    pub fn pitch(&self) -> usize {
+        self.raw().pitch as usize
+    }
+
+    /// Returns the pixel format of the surface.
+    #[must_use]
+    pub fn format(&self) -> &PixelFormat {
+        &self.format
+    }
+
+    fn len(&self) -> usize {
+        self.pitch() * self.height() as usize
+    }
+
+    /// Returns bytes of the pixels.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.raw().pixels.cast(), self.len()) }
+    }
+
+    /// Returns mutable bytes of the pixels.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.raw().pixels.cast(), self.len()) }
+    }
+
+    /// Returns the iterator of rows of the pixels, each row has [`Lock::pitch`] bytes.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.as_bytes().chunks(self.pitch())
+    }
+
+    /// Returns the iterator of mutable rows of the pixels, each row has [`Lock::pitch`] bytes.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> + '_ {
+        let pitch = self.pitch();
+        self.as_bytes_mut().chunks_mut(pitch)
+    }
+
+    /// Returns the color at `pos`, or `None` if `pos` is out of the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format.
+    #[must_use]
+    pub fn get_pixel(&self, pos: Point) -> Option<Rgba> {
+        let offset = self.offset_of(pos)?;
+        let bpp = self.format.bytes_per_pixel() as usize;
+        let pixel = Pixel::read_bytes(&self.as_bytes()[offset..offset + bpp]);
+        Some(self.format.rgba_from_pixel(pixel))
+    }
+
+    /// Writes the color at `pos`, and returns whether `pos` is in the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format.
+    pub fn set_pixel(&mut self, pos: Point, color: Rgba) -> bool {
+        let offset = match self.offset_of(pos) {
+            Some(offset) => offset,
+            None => return false,
+        };
+        let bpp = self.format.bytes_per_pixel() as usize;
+        let pixel = self.format.pixel_by_rgba(color);
+        pixel.write_bytes(&mut self.as_bytes_mut()[offset..offset + bpp]);
+        true
+    }
+
+    /// Returns the palette index at `pos`, or `None` if `pos` is out of the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not 8-bit indexed.
+    #[must_use]
+    pub fn get_index(&self, pos: Point) -> Option<u8> {
+        self.assert_indexed();
+        self.offset_of(pos).map(|offset| self.as_bytes()[offset])
+    }
+
+    /// Writes the palette index at `pos`, and returns whether `pos` is in the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not 8-bit indexed.
+    pub fn set_index(&mut self, pos: Point, index: u8) -> bool {
+        self.assert_indexed();
+        match self.offset_of(pos) {
+            Some(offset) => {
+                self.as_bytes_mut()[offset] = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the iterator of rows of the palette indices, each row has the width of the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not 8-bit indexed.
+    pub fn index_rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.assert_indexed();
+        let width = self.width() as usize;
+        self.rows().map(move |row| &row[..width])
+    }
+
+    /// Writes the palette indices returned from `f` for all the positions in the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not 8-bit indexed.
+    pub fn fill_indices_with(&mut self, mut f: impl FnMut(Point) -> u8) {
+        self.assert_indexed();
+        let width = self.width() as usize;
+        for (y, row) in self.rows_mut().enumerate() {
+            for (x, dst) in row[..width].iter_mut().enumerate() {
+                *dst = f(Point {
+                    x: x as i32,
+                    y: y as i32,
+                });
+            }
+        }
+    }
+
+    /// Returns the iterator of rows of the colors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format.
+    pub fn rgba_rows(&self) -> impl Iterator<Item = Vec<Rgba>> + '_ {
+        let bpp = self.bytes_per_pixel();
+        let width = self.width() as usize;
+        self.rows().map(move |row| {
+            row[..width * bpp]
+                .chunks(bpp)
+                .map(|bytes| self.format.rgba_from_pixel(Pixel::read_bytes(bytes)))
+                .collect()
+        })
+    }
+
+    /// Writes the colors returned from `f` for all the positions in the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format.
+    pub fn fill_with(&mut self, mut f: impl FnMut(Point) -> Rgba) {
+        let bpp = self.bytes_per_pixel();
+        let width = self.width() as usize;
+        let pitch = self.pitch();
+        let pixels =
+            unsafe { std::slice::from_raw_parts_mut(self.raw().pixels.cast::<u8>(), self.len()) };
+        for (y, row) in pixels.chunks_mut(pitch).enumerate() {
+            for (x, dst) in row[..width * bpp].chunks_mut(bpp).enumerate() {
+                let color = f(Point {
+                    x: x as i32,
+                    y: y as i32,
+                });
+                self.format.pixel_by_rgba(color).write_bytes(dst);
+            }
+        }
+    }
+
+    fn assert_indexed(&self) {
+        assert!(
+            matches!(
+                self.format.kind(),
+                PixelFormatKind::Bitmap {
+                    ty: BitmapPixelType::Index8,
+                    ..
+                }
+            ),
+            "format must be 8-bit indexed"
+        );
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        let bpp = self.format.bytes_per_pixel() as usize;
+        assert!((1..=4).contains(&bpp), "format must be packed or indexed");
+        bpp
+    }
+
+    fn offset_of(&self, Point { x, y }: Point) -> Option<usize> {
+        let bpp = self.bytes_per_pixel();
+        let in_surface =
+            0 <= x && (x as u32) < self.width() && 0 <= y && (y as u32) < self.height();
+        in_surface.then(|| y as usize * self.pitch() + x as usize * bpp)
+    }
+}
+
+impl Drop for Lock<'_> {
+    fn drop(&mut self) {
+        if self.locked {
+            unsafe { bind::SDL_UnlockSurface(self.surface.as_ptr()) }
+        }
+    }
+}
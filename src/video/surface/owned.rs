@@ -5,18 +5,38 @@ use std::{os::raw::c_int, ptr::NonNull};
 use super::Surface;
 use crate::{
     bind,
-    color::pixel::{kind::BppMask, PixelFormat},
+    color::pixel::{
+        kind::{BppMask, PixelFormatKind},
+        order::BitmapPixelOrder,
+        palette::Palette,
+        ty::BitmapPixelType,
+        PixelFormat,
+    },
     geo::Size,
     Result, Sdl, SdlError,
 };
 
 /// An owned [`Surface`] with raw pixel data.
-#[derive(Debug)]
 pub struct Owned {
     raw: NonNull<bind::SDL_Surface>,
+    pixels: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for Owned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Owned")
+            .field("raw", &self.raw)
+            .field("has_own_pixels", &self.pixels.is_some())
+            .finish()
+    }
 }
 
 impl Owned {
+    /// Wraps the raw surface pointer, the surface will be freed on dropped.
+    pub(crate) fn from_raw(raw: NonNull<bind::SDL_Surface>) -> Self {
+        Self { raw, pixels: None }
+    }
+
     /// Creates a new owned surface with its size and pixel format.
     ///
     /// # Errors
@@ -44,9 +64,101 @@ impl Owned {
         };
         NonNull::new(ptr).map_or_else(
             || Err(SdlError::Others { msg: Sdl::error() }),
-            |raw| Ok(Self { raw }),
+            |raw| Ok(Self::from_raw(raw)),
         )
     }
+
+    /// Creates a new owned surface with its size and pixel format kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface, or the format is unsupported.
+    pub fn with_kind(size: Size, kind: PixelFormatKind) -> Result<Self> {
+        let raw_kind = kind.as_raw();
+        let ptr = unsafe {
+            bind::SDL_CreateRGBSurfaceWithFormat(
+                0,
+                size.width as c_int,
+                size.height as c_int,
+                ((raw_kind >> 8) & 0xff) as c_int,
+                raw_kind,
+            )
+        };
+        NonNull::new(ptr).map_or_else(
+            || Err(SdlError::Others { msg: Sdl::error() }),
+            |raw| Ok(Self::from_raw(raw)),
+        )
+    }
+
+    /// Creates a new owned surface of 8-bit indices with the palette. The palette is shared with the surface, so changing its colors affects the surface.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface, or set the palette.
+    pub fn indexed(size: Size, palette: &Palette) -> Result<Self> {
+        let surface = Self::with_kind(
+            size,
+            PixelFormatKind::Bitmap {
+                ty: BitmapPixelType::Index8,
+                order: BitmapPixelOrder::None,
+            },
+        )?;
+        let ret = unsafe { bind::SDL_SetSurfacePalette(surface.raw.as_ptr(), palette.as_ptr()) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+        Ok(surface)
+    }
+
+    /// Creates a new owned surface over the pixels, without copying them. The pixels are held by the surface and released together on dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to create the surface, or the format is unsupported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pitch` is shorter than a row, or `pixels` is shorter than the rows of the height.
+    pub fn from_vec(
+        mut pixels: Vec<u8>,
+        size: Size,
+        pitch: usize,
+        kind: PixelFormatKind,
+    ) -> Result<Self> {
+        let raw = unsafe { create_from(&mut pixels, size, pitch, kind)? };
+        Ok(Self {
+            raw,
+            pixels: Some(pixels),
+        })
+    }
+}
+
+/// Creates the surface over `pixels`, the surface must be freed before `pixels` dropped.
+pub(super) unsafe fn create_from(
+    pixels: &mut [u8],
+    size: Size,
+    pitch: usize,
+    kind: PixelFormatKind,
+) -> Result<NonNull<bind::SDL_Surface>> {
+    assert!(
+        kind.row_bytes(size.width) <= pitch,
+        "pitch must cover a row"
+    );
+    assert!(
+        kind.required_len(size.height, pitch) <= pixels.len(),
+        "pixels must cover the size"
+    );
+    let raw_kind = kind.as_raw();
+    let bits_per_pixel = (raw_kind >> 8) & 0xff;
+    let ptr = bind::SDL_CreateRGBSurfaceWithFormatFrom(
+        pixels.as_mut_ptr().cast(),
+        size.width as c_int,
+        size.height as c_int,
+        bits_per_pixel as c_int,
+        pitch as c_int,
+        raw_kind,
+    );
+    NonNull::new(ptr).ok_or_else(|| SdlError::Others { msg: Sdl::error() })
 }
 
 impl Drop for Owned {
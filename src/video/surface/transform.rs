@@ -0,0 +1,394 @@
+//! Transforming a [`Surface`] into a new surface, such as rotating, flipping and zooming.
+
+use std::os::raw::c_int;
+use std::ptr::NonNull;
+
+use crate::color::pixel::{
+    kind::PixelFormatKind, layout::PackedPixelLayout, order::PackedPixelOrder, ty::PackedPixelType,
+    Pixel, PixelFormat,
+};
+use crate::geo::Size;
+use crate::renderer::PasteExFlip;
+use crate::{bind, Result, Sdl, SdlError};
+
+use super::{lock::Lock, owned::Owned, RawSurface, StretchFilter, Surface};
+
+/// The format to interpolate the pixels in.
+const SMOOTH_FORMAT: PixelFormatKind = PixelFormatKind::Packed {
+    ty: PackedPixelType::_32,
+    order: PackedPixelOrder::Argb,
+    layout: PackedPixelLayout::_8888,
+};
+
+/// An extension for a [`Surface`] to make the transformed surface, for baking the variants of sprites on loading.
+///
+/// The transformed surface has the same pixel format as the source, and inherits the palette, the color key and the blend mode. The transforms without interpolation copy the pixels as is, so they also keep the indices of the indexed formats.
+pub trait TransformExt {
+    /// Returns the surface rotated clockwise by 90 degrees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format of bytes per pixel.
+    fn rotated_90(&self) -> Result<Owned>;
+
+    /// Returns the surface rotated by 180 degrees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format of bytes per pixel.
+    fn rotated_180(&self) -> Result<Owned>;
+
+    /// Returns the surface rotated clockwise by 270 degrees, that is counter-clockwise by 90 degrees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format of bytes per pixel.
+    fn rotated_270(&self) -> Result<Owned>;
+
+    /// Returns the surface flipped by `flip`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format of bytes per pixel.
+    fn flipped(&self, flip: PasteExFlip) -> Result<Owned>;
+
+    /// Returns the surface rotated clockwise by `degrees` around its center and zoomed by `zoom`. The new surface is large enough to contain the whole rotated image, and the uncovered pixels are filled with the color key if set, otherwise zero such as transparent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate or convert the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `zoom` is not positive, or the format is not a packed or indexed format of bytes per pixel.
+    fn rotozoomed(&self, degrees: f64, zoom: f64, filter: StretchFilter) -> Result<Owned>;
+
+    /// Returns the surface zoomed by the factors, rounded to the nearest size.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate or convert the surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the factors are not positive.
+    fn zoomed(&self, horizontal: f64, vertical: f64, filter: StretchFilter) -> Result<Owned>;
+}
+
+impl<T: Surface> TransformExt for T {
+    fn rotated_90(&self) -> Result<Owned> {
+        let Size { width, height } = size_of(self.as_ptr());
+        remap(
+            self,
+            Size {
+                width: height,
+                height: width,
+            },
+            |x, y| Some((y, height as usize - 1 - x)),
+        )
+    }
+
+    fn rotated_180(&self) -> Result<Owned> {
+        let size = size_of(self.as_ptr());
+        let (width, height) = (size.width as usize, size.height as usize);
+        remap(self, size, |x, y| Some((width - 1 - x, height - 1 - y)))
+    }
+
+    fn rotated_270(&self) -> Result<Owned> {
+        let Size { width, height } = size_of(self.as_ptr());
+        remap(
+            self,
+            Size {
+                width: height,
+                height: width,
+            },
+            |x, y| Some((width as usize - 1 - y, x)),
+        )
+    }
+
+    fn flipped(&self, flip: PasteExFlip) -> Result<Owned> {
+        let size = size_of(self.as_ptr());
+        let (width, height) = (size.width as usize, size.height as usize);
+        let horizontal = flip.contains(PasteExFlip::HORIZONTAL);
+        let vertical = flip.contains(PasteExFlip::VERTICAL);
+        remap(self, size, |x, y| {
+            Some((
+                if horizontal { width - 1 - x } else { x },
+                if vertical { height - 1 - y } else { y },
+            ))
+        })
+    }
+
+    fn rotozoomed(&self, degrees: f64, zoom: f64, filter: StretchFilter) -> Result<Owned> {
+        assert!(0.0 < zoom, "zoom must be positive");
+        let src_size = size_of(self.as_ptr());
+        let (src_width, src_height) = (f64::from(src_size.width), f64::from(src_size.height));
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (sin, cos) = (snap(sin), snap(cos));
+        let (zoomed_width, zoomed_height) = (src_width * zoom, src_height * zoom);
+        let size = Size {
+            width: ((zoomed_width * cos.abs() + zoomed_height * sin.abs()).round() as u32).max(1),
+            height: ((zoomed_width * sin.abs() + zoomed_height * cos.abs()).round() as u32).max(1),
+        };
+        let (half_width, half_height) = (f64::from(size.width) / 2.0, f64::from(size.height) / 2.0);
+        // Maps the center of the destination pixel back into the source coordinates.
+        let inverse = |x: usize, y: usize| {
+            let (dx, dy) = (x as f64 + 0.5 - half_width, y as f64 + 0.5 - half_height);
+            (
+                (cos * dx + sin * dy) / zoom + src_width / 2.0,
+                (-sin * dx + cos * dy) / zoom + src_height / 2.0,
+            )
+        };
+        match filter {
+            StretchFilter::Nearest => remap(self, size, |x, y| {
+                let (sx, sy) = inverse(x, y);
+                (0.0 <= sx && sx < src_width && 0.0 <= sy && sy < src_height)
+                    .then(|| (sx as usize, sy as usize))
+            }),
+            StretchFilter::Linear => {
+                let source = read_argb(&self.convert_to(SMOOTH_FORMAT)?)?;
+                let mut smooth = Owned::with_kind(size, SMOOTH_FORMAT)?;
+                let width = size.width as usize;
+                let mut uncovered = vec![false; width * size.height as usize];
+                {
+                    let mut lock = smooth.lock()?;
+                    for (y, row) in lock.rows_mut().enumerate() {
+                        for (x, dst) in row[..width * 4].chunks_mut(4).enumerate() {
+                            let (sx, sy) = inverse(x, y);
+                            uncovered[y * width + x] =
+                                !(0.0 <= sx && sx < src_width && 0.0 <= sy && sy < src_height);
+                            let pixel = sample_bilinear(&source, src_size, sx, sy);
+                            dst.copy_from_slice(&pixel.to_ne_bytes());
+                        }
+                    }
+                }
+                let mut dst = restore_format(self.as_ptr(), &smooth)?;
+                let mut key = 0;
+                if unsafe { bind::SDL_GetColorKey(self.as_ptr().as_ptr(), &mut key) } == 0 {
+                    let mut lock = dst.lock()?;
+                    let bpp = byte_pixel_size(lock.format());
+                    for (y, row) in lock.rows_mut().enumerate() {
+                        for (x, pixel) in row[..width * bpp].chunks_mut(bpp).enumerate() {
+                            if uncovered[y * width + x] {
+                                Pixel::from_u32(key).write_bytes(pixel);
+                            }
+                        }
+                    }
+                }
+                Ok(dst)
+            }
+        }
+    }
+
+    fn zoomed(&self, horizontal: f64, vertical: f64, filter: StretchFilter) -> Result<Owned> {
+        assert!(
+            0.0 < horizontal && 0.0 < vertical,
+            "zoom factors must be positive"
+        );
+        let src_size = size_of(self.as_ptr());
+        let size = Size {
+            width: ((f64::from(src_size.width) * horizontal).round() as u32).max(1),
+            height: ((f64::from(src_size.height) * vertical).round() as u32).max(1),
+        };
+        match filter {
+            StretchFilter::Nearest => {
+                let dst = blank_like(self.as_ptr(), size)?;
+                self.stretch_to(None, &dst, None, StretchFilter::Nearest)?;
+                Ok(dst)
+            }
+            StretchFilter::Linear => {
+                let source = self.convert_to(SMOOTH_FORMAT)?;
+                let smooth = Owned::with_kind(size, SMOOTH_FORMAT)?;
+                source.stretch_to(None, &smooth, None, StretchFilter::Linear)?;
+                restore_format(self.as_ptr(), &smooth)
+            }
+        }
+    }
+}
+
+fn size_of(surface: NonNull<RawSurface>) -> Size {
+    let raw = unsafe { surface.as_ref() };
+    Size {
+        width: raw.w as u32,
+        height: raw.h as u32,
+    }
+}
+
+/// Rounds the nearly exact value for the right angles, to avoid an extra row or column.
+fn snap(value: f64) -> f64 {
+    let rounded = value.round();
+    if (value - rounded).abs() < 1e-9 {
+        rounded
+    } else {
+        value
+    }
+}
+
+/// Creates the blank surface in the size, with the same format and attributes as `src`.
+fn blank_like(src: NonNull<RawSurface>, size: Size) -> Result<Owned> {
+    let format = unsafe { &*src.as_ref().format };
+    let ptr = unsafe {
+        bind::SDL_CreateRGBSurfaceWithFormat(
+            0,
+            size.width as c_int,
+            size.height as c_int,
+            format.BitsPerPixel.into(),
+            format.format,
+        )
+    };
+    let dst = NonNull::new(ptr)
+        .map(Owned::from_raw)
+        .ok_or_else(|| SdlError::Others { msg: Sdl::error() })?;
+    if !format.palette.is_null() {
+        let ret = unsafe { bind::SDL_SetSurfacePalette(dst.as_ptr().as_ptr(), format.palette) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+    }
+    copy_attributes(src, &dst)?;
+    Ok(dst)
+}
+
+/// Copies the color key and the blend mode of `src` into `dst`.
+fn copy_attributes(src: NonNull<RawSurface>, dst: &Owned) -> Result<()> {
+    let dst = dst.as_ptr().as_ptr();
+    let mut key = 0;
+    let mut mode = bind::SDL_BLENDMODE_NONE;
+    let ret = unsafe {
+        let mut ret = 0;
+        if bind::SDL_HasColorKey(src.as_ptr()) == bind::SDL_TRUE
+            && bind::SDL_GetColorKey(src.as_ptr(), &mut key) == 0
+        {
+            ret |= bind::SDL_SetColorKey(dst, bind::SDL_TRUE as _, key);
+        }
+        if bind::SDL_GetSurfaceBlendMode(src.as_ptr(), &mut mode) == 0 {
+            ret |= bind::SDL_SetSurfaceBlendMode(dst, mode);
+        }
+        ret
+    };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(SdlError::Others { msg: Sdl::error() })
+    }
+}
+
+/// Converts the interpolated surface back into the format of `src`.
+fn restore_format(src: NonNull<RawSurface>, smooth: &Owned) -> Result<Owned> {
+    let format = unsafe { PixelFormat::borrowed(NonNull::new(src.as_ref().format).unwrap()) };
+    let dst = smooth.convert(&format)?;
+    copy_attributes(src, &dst)?;
+    Ok(dst)
+}
+
+/// Creates the surface in the size, copying the source pixel at `map(x, y)` into each `(x, y)`. The pixels mapped into `None` are filled with the color key if set, otherwise zero.
+fn remap<S: Surface>(
+    src: &S,
+    size: Size,
+    map: impl Fn(usize, usize) -> Option<(usize, usize)>,
+) -> Result<Owned> {
+    let mut dst = blank_like(src.as_ptr(), size)?;
+    let mut key = 0;
+    if unsafe { bind::SDL_GetColorKey(src.as_ptr().as_ptr(), &mut key) } == 0 {
+        let ret = unsafe { bind::SDL_FillRect(dst.as_ptr().as_ptr(), std::ptr::null(), key) };
+        if ret != 0 {
+            return Err(SdlError::Others { msg: Sdl::error() });
+        }
+    }
+    let src_lock = Lock::read(src)?;
+    let bpp = byte_pixel_size(src_lock.format());
+    let src_pitch = src_lock.pitch();
+    let src_bytes = src_lock.as_bytes();
+    let mut dst_lock = dst.lock()?;
+    let width = size.width as usize;
+    for (y, row) in dst_lock.rows_mut().enumerate() {
+        for (x, dst) in row[..width * bpp].chunks_mut(bpp).enumerate() {
+            if let Some((sx, sy)) = map(x, y) {
+                let offset = sy * src_pitch + sx * bpp;
+                dst.copy_from_slice(&src_bytes[offset..offset + bpp]);
+            }
+        }
+    }
+    drop(dst_lock);
+    Ok(dst)
+}
+
+/// Returns the bytes per pixel, asserting the pixels are in whole bytes. The indexed formats of less than 8 bits are packed into bytes, so they cannot be copied by each pixel.
+fn byte_pixel_size(format: &PixelFormat) -> usize {
+    let bpp = format.bytes_per_pixel() as usize;
+    assert!(
+        8 <= format.bits_per_pixel() && (1..=4).contains(&bpp),
+        "format must be a packed or indexed format of bytes per pixel"
+    );
+    bpp
+}
+
+/// Reads the pixels of the surface in [`SMOOTH_FORMAT`] without the row padding.
+fn read_argb(surface: &Owned) -> Result<Vec<u32>> {
+    let lock = Lock::read(surface)?;
+    let width = lock.width() as usize;
+    Ok(lock
+        .rows()
+        .flat_map(|row| {
+            row[..width * 4]
+                .chunks(4)
+                .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        })
+        .collect())
+}
+
+/// Samples the pixel at `(x, y)` with bilinear interpolation of the premultiplied colors. Outside of the source is transparent.
+fn sample_bilinear(pixels: &[u32], size: Size, x: f64, y: f64) -> u32 {
+    let (fx, fy) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (fx.floor(), fy.floor());
+    let (tx, ty) = (fx - x0, fy - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let fetch = |x: i64, y: i64| {
+        if x < 0 || i64::from(size.width) <= x || y < 0 || i64::from(size.height) <= y {
+            return [0.0; 4];
+        }
+        let pixel = pixels[y as usize * size.width as usize + x as usize];
+        let alpha = f64::from((pixel >> 24) as u8) / 255.0;
+        [
+            f64::from((pixel >> 16) as u8) * alpha,
+            f64::from((pixel >> 8) as u8) * alpha,
+            f64::from(pixel as u8) * alpha,
+            alpha,
+        ]
+    };
+    let corners = [
+        (fetch(x0, y0), (1.0 - tx) * (1.0 - ty)),
+        (fetch(x0 + 1, y0), tx * (1.0 - ty)),
+        (fetch(x0, y0 + 1), (1.0 - tx) * ty),
+        (fetch(x0 + 1, y0 + 1), tx * ty),
+    ];
+    let mut sum = [0.0; 4];
+    for (color, weight) in &corners {
+        for (sum, component) in sum.iter_mut().zip(color) {
+            *sum += component * weight;
+        }
+    }
+    let [r, g, b, alpha] = sum;
+    if alpha <= 0.0 {
+        return 0;
+    }
+    let channel = |value: f64| (value / alpha).round().clamp(0.0, 255.0) as u32;
+    let a = (alpha * 255.0).round().clamp(0.0, 255.0) as u32;
+    (a << 24) | (channel(r) << 16) | (channel(g) << 8) | channel(b)
+}
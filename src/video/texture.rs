@@ -4,14 +4,16 @@ use static_assertions::assert_not_impl_all;
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use crate::color::Rgb;
+use crate::color::{pixel::kind::PixelFormatKind, BlendMode, Rgb};
 use crate::geo::{Rect, Size};
 use crate::renderer::Renderer;
 use crate::surface::Surface;
-use crate::{bind, EnumInt, Result, Sdl, SdlError};
+use crate::{as_raw, bind, EnumInt, Result, Sdl, SdlError};
 
+pub mod atlas;
 pub mod lock;
 mod query;
+pub mod registry;
 
 use lock::Lock;
 pub use query::*;
@@ -48,6 +50,36 @@ impl TextureAccess {
     }
 }
 
+/// A scaling mode of the texture on rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ScaleMode {
+    /// Nearest pixel sampling, keeps pixel arts sharp.
+    Nearest,
+    /// Linear filtering.
+    Linear,
+    /// Anisotropic filtering.
+    Best,
+}
+
+impl ScaleMode {
+    fn from_raw(raw: bind::SDL_ScaleMode) -> Self {
+        match raw {
+            bind::SDL_ScaleModeLinear => ScaleMode::Linear,
+            bind::SDL_ScaleModeBest => ScaleMode::Best,
+            _ => ScaleMode::Nearest,
+        }
+    }
+
+    fn as_raw(self) -> bind::SDL_ScaleMode {
+        match self {
+            ScaleMode::Nearest => bind::SDL_ScaleModeNearest,
+            ScaleMode::Linear => bind::SDL_ScaleModeLinear,
+            ScaleMode::Best => bind::SDL_ScaleModeBest,
+        }
+    }
+}
+
 /// A texture used to draw the image to [`Renderer`].
 pub struct Texture<'renderer> {
     texture: NonNull<bind::SDL_Texture>,
@@ -66,15 +98,18 @@ impl std::fmt::Debug for Texture<'_> {
 assert_not_impl_all!(Texture: Send, Sync);
 
 impl<'renderer> Texture<'renderer> {
-    /// Constructs a texture from the renderer with access type.
+    /// Constructs a texture from the renderer with access type. The size and pixel format are the same as the window, or the surface if the renderer draws into it.
     ///
     /// # Errors
     ///
     /// Returns `Err` if failed to allocate, no rendering context was active, the format was unsupported, or the width or height were out of range.
     pub fn new(renderer: &'renderer Renderer<'renderer>, access: TextureAccess) -> Result<Self> {
         use super::window::ConfigExt;
-        let Size { width, height } = renderer.window().size();
-        let pixel_format = renderer.window().pixel_format();
+        let Size { width, height } = match renderer.output_window() {
+            Some(window) => window.size(),
+            None => renderer.output_size()?,
+        };
+        let pixel_format = renderer.output_format();
         NonNull::new(unsafe {
             bind::SDL_CreateTexture(
                 renderer.as_ptr(),
@@ -96,6 +131,38 @@ impl<'renderer> Texture<'renderer> {
         )
     }
 
+    /// Constructs a texture from the renderer with the size, pixel format and access type.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to allocate, no rendering context was active, the format was unsupported, or the width or height were out of range.
+    pub fn with_format(
+        renderer: &'renderer Renderer<'renderer>,
+        Size { width, height }: Size,
+        kind: PixelFormatKind,
+        access: TextureAccess,
+    ) -> Result<Self> {
+        NonNull::new(unsafe {
+            bind::SDL_CreateTexture(
+                renderer.as_ptr(),
+                kind.as_raw(),
+                access.as_raw() as i32,
+                width as i32,
+                height as i32,
+            )
+        })
+        .map_or_else(
+            || Err(SdlError::Others { msg: Sdl::error() }),
+            |texture| {
+                Ok(Self {
+                    texture,
+                    clip: None,
+                    _phantom: PhantomData,
+                })
+            },
+        )
+    }
+
     /// Constructs a texture from the [`Surface`]. The texture will be readonly and the access type will be [`TextureAccess::Static`].
     pub fn from_surface(
         renderer: &'renderer Renderer<'renderer>,
@@ -165,6 +232,197 @@ impl<'renderer> Texture<'renderer> {
         }
     }
 
+    /// Returns the blend mode of the texture.
+    pub fn blend_mode(&self) -> BlendMode {
+        let mut raw = 0;
+        let ret = unsafe { bind::SDL_GetTextureBlendMode(self.as_ptr(), &mut raw) };
+        if ret != 0 {
+            Sdl::error_then_panic("Getting texture blend mode");
+        }
+        raw.into()
+    }
+
+    /// Sets the blend mode of the texture, used on pasting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the blend mode is unsupported by the renderer.
+    pub fn set_blend_mode(&self, mode: BlendMode) -> Result<()> {
+        let ret = unsafe { bind::SDL_SetTextureBlendMode(self.as_ptr(), mode.into()) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Returns the scale mode of the texture.
+    #[must_use]
+    pub fn scale_mode(&self) -> ScaleMode {
+        let mut raw = bind::SDL_ScaleModeNearest;
+        let ret = unsafe { bind::SDL_GetTextureScaleMode(self.as_ptr(), &mut raw) };
+        if ret != 0 {
+            Sdl::error_then_panic("Getting texture scale mode");
+        }
+        ScaleMode::from_raw(raw)
+    }
+
+    /// Sets the scale mode of the texture, used on scaling in pasting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the scale mode is unsupported.
+    pub fn set_scale_mode(&self, mode: ScaleMode) -> Result<()> {
+        let ret = unsafe { bind::SDL_SetTextureScaleMode(self.as_ptr(), mode.as_raw()) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Updates the pixels in `area`, or whole if `None`, with `pixels` in the texture format. `pitch` is the number of bytes in a row of `pixels`.
+    ///
+    /// This is slow, so you should use [`TextureAccess::Streaming`] and [`Texture::lock`] for frequently updated textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `area` was out of the texture, or failed to update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pitch` was shorter than a row of `area`, or `pixels` was too short for `area` and `pitch`.
+    pub fn update(&self, area: Option<Rect>, pixels: &[u8], pitch: usize) -> Result<()> {
+        let Size { width, height } = area.unwrap_or_else(|| self.whole_area()).size;
+        let kind = self.format();
+        assert!(kind.row_bytes(width) <= pitch, "pitch must cover a row");
+        assert!(
+            kind.required_len(height, pitch) <= pixels.len(),
+            "pixels must cover the area"
+        );
+        let area = area.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_UpdateTexture(
+                self.as_ptr(),
+                as_raw(&area),
+                pixels.as_ptr().cast(),
+                pitch as i32,
+            )
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Updates the pixels in `area`, or whole if `None`, with separated Y, U and V planes. The texture format must be a planar YUV format such as YV12 or IYUV.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the texture format is not planar YUV, `area` was out of the texture, or failed to update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some pitch was shorter than a row of the plane, or some plane was too short for `area` and its pitch.
+    pub fn update_yuv(
+        &self,
+        area: Option<Rect>,
+        (y_plane, y_pitch): (&[u8], usize),
+        (u_plane, u_pitch): (&[u8], usize),
+        (v_plane, v_pitch): (&[u8], usize),
+    ) -> Result<()> {
+        let Size { width, height } = area.unwrap_or_else(|| self.whole_area()).size;
+        let (width, height) = (width as usize, height as usize);
+        let (chroma_width, chroma_height) = (width / 2 + width % 2, height / 2 + height % 2);
+        assert!(width <= y_pitch, "Y pitch must cover a row");
+        assert!(chroma_width <= u_pitch, "U pitch must cover a row");
+        assert!(chroma_width <= v_pitch, "V pitch must cover a row");
+        assert!(
+            y_pitch * height <= y_plane.len(),
+            "Y plane must cover the area"
+        );
+        assert!(
+            u_pitch * chroma_height <= u_plane.len(),
+            "U plane must cover the area"
+        );
+        assert!(
+            v_pitch * chroma_height <= v_plane.len(),
+            "V plane must cover the area"
+        );
+        let area = area.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_UpdateYUVTexture(
+                self.as_ptr(),
+                as_raw(&area),
+                y_plane.as_ptr(),
+                y_pitch as i32,
+                u_plane.as_ptr(),
+                u_pitch as i32,
+                v_plane.as_ptr(),
+                v_pitch as i32,
+            )
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    /// Updates the pixels in `area`, or whole if `None`, with a Y plane and an interleaved UV plane. The texture format must be NV12 or NV21.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the texture format is not NV12 or NV21, `area` was out of the texture, or failed to update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some pitch was shorter than a row of the plane, or some plane was too short for `area` and its pitch.
+    pub fn update_nv(
+        &self,
+        area: Option<Rect>,
+        (y_plane, y_pitch): (&[u8], usize),
+        (uv_plane, uv_pitch): (&[u8], usize),
+    ) -> Result<()> {
+        let Size { width, height } = area.unwrap_or_else(|| self.whole_area()).size;
+        let (width, height) = (width as usize, height as usize);
+        let (chroma_width, chroma_height) = (width / 2 + width % 2, height / 2 + height % 2);
+        assert!(width <= y_pitch, "Y pitch must cover a row");
+        assert!(2 * chroma_width <= uv_pitch, "UV pitch must cover a row");
+        assert!(
+            y_pitch * height <= y_plane.len(),
+            "Y plane must cover the area"
+        );
+        assert!(
+            uv_pitch * chroma_height <= uv_plane.len(),
+            "UV plane must cover the area"
+        );
+        let area = area.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_UpdateNVTexture(
+                self.as_ptr(),
+                as_raw(&area),
+                y_plane.as_ptr(),
+                y_pitch as i32,
+                uv_plane.as_ptr(),
+                uv_pitch as i32,
+            )
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(SdlError::Others { msg: Sdl::error() })
+        }
+    }
+
+    fn whole_area(&self) -> Rect {
+        Rect {
+            up_left: crate::geo::Point::default(),
+            size: self.size(),
+        }
+    }
+
     /// Obtains the lock for the texture in area, or whole if `None`.
     pub fn lock(&'renderer mut self, area: Option<Rect>) -> Lock<'renderer> {
         Lock::new(self, area)
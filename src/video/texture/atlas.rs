@@ -0,0 +1,604 @@
+//! Texture atlas, packing many surfaces into a few large textures.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::color::pixel::{
+    kind::PixelFormatKind, layout::PackedPixelLayout, order::PackedPixelOrder, ty::PackedPixelType,
+};
+use crate::color::BlendMode;
+use crate::geo::{FRect, Point, Rect, Size};
+use crate::renderer::{Paster, Renderer};
+use crate::surface::{bmp::BmpSaveExt, owned::Owned, Surface};
+use crate::{as_raw, bind, Result, Sdl, SdlError};
+
+use super::{Texture, TextureAccess};
+
+const ATLAS_FORMAT: PixelFormatKind = PixelFormatKind::Packed {
+    ty: PackedPixelType::_32,
+    order: PackedPixelOrder::Argb,
+    layout: PackedPixelLayout::_8888,
+};
+
+/// A segment of the skyline, the top edge of packed rectangles.
+#[derive(Debug, Clone, Copy)]
+struct SkylineNode {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// A rectangle packer with the skyline bottom-left algorithm.
+#[derive(Debug, Clone)]
+struct SkylinePacker {
+    size: Size,
+    nodes: Vec<SkylineNode>,
+}
+
+impl SkylinePacker {
+    fn new(size: Size) -> Self {
+        Self {
+            size,
+            nodes: vec![SkylineNode {
+                x: 0,
+                y: 0,
+                width: size.width,
+            }],
+        }
+    }
+
+    /// Returns the y coordinate to place the rect at the node, or `None` if it does not fit.
+    fn fit(&self, index: usize, Size { width, height }: Size) -> Option<u32> {
+        let x = self.nodes[index].x;
+        if self.size.width < x + width {
+            return None;
+        }
+        let mut y = 0;
+        let mut remaining = width;
+        for node in &self.nodes[index..] {
+            if remaining == 0 {
+                break;
+            }
+            y = y.max(node.y);
+            if self.size.height < y + height {
+                return None;
+            }
+            remaining = remaining.saturating_sub(node.width);
+        }
+        Some(y)
+    }
+
+    /// Finds the place for the rect of `size`, and marks it used.
+    fn insert(&mut self, size: Size) -> Option<Point> {
+        let (best_index, best_y) = (0..self.nodes.len())
+            .filter_map(|index| self.fit(index, size).map(|y| (index, y)))
+            .min_by_key(|&(index, y)| (y + size.height, self.nodes[index].x))?;
+        let x = self.nodes[best_index].x;
+        self.nodes.insert(
+            best_index,
+            SkylineNode {
+                x,
+                y: best_y + size.height,
+                width: size.width,
+            },
+        );
+
+        let index = best_index + 1;
+        while index < self.nodes.len() {
+            let prev = self.nodes[index - 1];
+            let prev_end = prev.x + prev.width;
+            let node = &mut self.nodes[index];
+            if prev_end <= node.x {
+                break;
+            }
+            let shrink = prev_end - node.x;
+            if node.width <= shrink {
+                self.nodes.remove(index);
+                continue;
+            }
+            node.x += shrink;
+            node.width -= shrink;
+            break;
+        }
+
+        let mut index = 0;
+        while index + 1 < self.nodes.len() {
+            if self.nodes[index].y == self.nodes[index + 1].y {
+                self.nodes[index].width += self.nodes[index + 1].width;
+                self.nodes.remove(index + 1);
+            } else {
+                index += 1;
+            }
+        }
+
+        Some(Point {
+            x: x as i32,
+            y: best_y as i32,
+        })
+    }
+}
+
+/// Finds the place for the rect of `size` in the pages of `packers`, trying with the padding first. A new page is added if no pages have enough space.
+fn pack(
+    packers: &mut Vec<SkylinePacker>,
+    page_size: Size,
+    padding: u32,
+    size: Size,
+) -> Result<(usize, Point)> {
+    if page_size.width < size.width || page_size.height < size.height {
+        return Err(SdlError::Others {
+            msg: "the surface is larger than the atlas page".into(),
+        });
+    }
+    let padded = Size {
+        width: size.width + padding,
+        height: size.height + padding,
+    };
+    let found = packers.iter_mut().enumerate().find_map(|(index, packer)| {
+        packer
+            .insert(padded)
+            .or_else(|| packer.insert(size))
+            .map(|up_left| (index, up_left))
+    });
+    if let Some(found) = found {
+        return Ok(found);
+    }
+    let mut packer = SkylinePacker::new(page_size);
+    let up_left = packer
+        .insert(padded)
+        .or_else(|| packer.insert(size))
+        .expect("the surface must fit into an empty page");
+    packers.push(packer);
+    Ok((packers.len() - 1, up_left))
+}
+
+/// Packs the rects of `sizes` into new pages, sorted by height. Returns the packers of the pages and the page index and the area of each rect.
+fn plan(
+    sizes: &[Size],
+    page_size: Size,
+    padding: u32,
+) -> Result<(Vec<SkylinePacker>, Vec<(usize, Rect)>)> {
+    let mut order: Vec<_> = (0..sizes.len()).collect();
+    order.sort_by_key(|&id| std::cmp::Reverse((sizes[id].height, sizes[id].width)));
+    let mut packers = vec![];
+    let mut placements = vec![None; sizes.len()];
+    for id in order {
+        let (page, up_left) = pack(&mut packers, page_size, padding, sizes[id])?;
+        placements[id] = Some((
+            page,
+            Rect {
+                up_left,
+                size: sizes[id],
+            },
+        ));
+    }
+    let placements = placements
+        .into_iter()
+        .map(|placement| placement.expect("all the rects must be placed"))
+        .collect();
+    Ok((packers, placements))
+}
+
+/// The id of the next atlas, to tell which atlas an [`AtlasRegion`] belongs to.
+static NEXT_ATLAS_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A packed surface in the atlas.
+#[derive(Debug)]
+struct AtlasEntry {
+    page: usize,
+    area: Rect,
+    source: Owned,
+}
+
+/// A handle of the packed region in [`TextureAtlas`], can be pasted by [`Paster::paste_region`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AtlasRegion {
+    atlas: usize,
+    id: usize,
+}
+
+/// A texture atlas, packing many surfaces into one or more large textures. This reduces the number of textures and switching them on rendering.
+///
+/// The atlas keeps the packed surfaces, so it can re-pack and restore the pages.
+#[derive(Debug)]
+pub struct TextureAtlas<'renderer> {
+    renderer: &'renderer Renderer<'renderer>,
+    id: usize,
+    page_size: Size,
+    padding: u32,
+    pages: Vec<Texture<'renderer>>,
+    packers: Vec<SkylinePacker>,
+    entries: Vec<AtlasEntry>,
+}
+
+impl<'renderer> TextureAtlas<'renderer> {
+    /// Constructs an empty atlas, whose pages will be created with `page_size`. The padding between regions is 1 pixel by default.
+    pub fn new(renderer: &'renderer Renderer<'renderer>, page_size: Size) -> Self {
+        Self {
+            renderer,
+            id: NEXT_ATLAS_ID.fetch_add(1, Ordering::Relaxed),
+            page_size,
+            padding: 1,
+            pages: vec![],
+            packers: vec![],
+            entries: vec![],
+        }
+    }
+
+    /// Sets the padding pixels between regions, to prevent bleeding on filtering.
+    #[must_use]
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the number of pages.
+    #[must_use]
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the number of packed regions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the atlas has no regions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the texture of the page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` is out of range.
+    #[must_use]
+    pub fn page_texture(&self, page: usize) -> &Texture<'renderer> {
+        &self.pages[page]
+    }
+
+    /// Returns the page index and the area in the page of the region, or `None` if the region was issued by another atlas.
+    #[must_use]
+    pub fn region(&self, region: AtlasRegion) -> Option<(usize, Rect)> {
+        self.entry(region).map(|entry| (entry.page, entry.area))
+    }
+
+    /// Returns the texture and the area in it of the region, or `None` if the region was issued by another atlas.
+    #[must_use]
+    pub fn region_texture(&self, region: AtlasRegion) -> Option<(&Texture<'renderer>, Rect)> {
+        self.entry(region)
+            .map(|entry| (&self.pages[entry.page], entry.area))
+    }
+
+    fn entry(&self, region: AtlasRegion) -> Option<&AtlasEntry> {
+        if region.atlas != self.id {
+            return None;
+        }
+        self.entries.get(region.id)
+    }
+
+    /// Packs the surface into the atlas, adding a new page if no pages have enough space.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the surface is larger than the page, or failed to convert the surface or to create the page.
+    pub fn add(&mut self, surface: &impl Surface) -> Result<AtlasRegion> {
+        let source = surface.convert_to(ATLAS_FORMAT)?;
+        let id = self.entries.len();
+        let (page, area) = self.place(&source)?;
+        self.entries.push(AtlasEntry { page, area, source });
+        Ok(AtlasRegion { atlas: self.id, id })
+    }
+
+    /// Re-packs all the regions into new pages, sorted by height. This may reduce the number of pages after many additions. Regions obtained before are still valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to create the pages.
+    pub fn repack(&mut self) -> Result<()> {
+        // Builds new pages aside, and keeps the old pages and regions if failed on the way.
+        let sizes: Vec<_> = self.entries.iter().map(|entry| entry.area.size).collect();
+        let (packers, placements) = plan(&sizes, self.page_size, self.padding)?;
+        let pages = packers
+            .iter()
+            .map(|_| new_page(self.renderer, self.page_size))
+            .collect::<Result<Vec<_>>>()?;
+        for (entry, &(page, area)) in self.entries.iter().zip(&placements) {
+            upload(&pages[page], &entry.source, area)?;
+        }
+        self.pages = pages;
+        self.packers = packers;
+        for (entry, (page, area)) in self.entries.iter_mut().zip(placements) {
+            entry.page = page;
+            entry.area = area;
+        }
+        Ok(())
+    }
+
+    /// Uploads all the packed surfaces to the pages again. Use this when the contents of the textures were lost, such as the render targets were reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to update the textures.
+    pub fn restore(&mut self) -> Result<()> {
+        for page in &self.pages {
+            clear_page(page, self.page_size)?;
+        }
+        for entry in &self.entries {
+            upload(&self.pages[entry.page], &entry.source, entry.area)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the pixels of the page into a new surface, for debugging.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to read the pixels from the texture.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` is out of range.
+    pub fn page_surface(&mut self, page: usize) -> Result<Owned> {
+        let renderer = self.renderer;
+        renderer.with_target(&mut self.pages[page], |frame| {
+            frame.renderer().read_pixels(None, ATLAS_FORMAT)
+        })?
+    }
+
+    /// Saves the pixels of the page as BMP format, for debugging.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if failed to read the pixels from the texture, or to save an image to the file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` is out of range.
+    pub fn save_page_bmp(&mut self, page: usize, file_name: &str) -> Result<()> {
+        self.page_surface(page)?
+            .save_bmp(file_name)
+            .map_err(|err| SdlError::Others { msg: err.0 })
+    }
+
+    fn place(&mut self, source: &Owned) -> Result<(usize, Rect)> {
+        let raw = unsafe { source.as_ptr().as_ref() };
+        let size = Size {
+            width: raw.w as u32,
+            height: raw.h as u32,
+        };
+        let (page, up_left) = pack(&mut self.packers, self.page_size, self.padding, size)?;
+        if page == self.pages.len() {
+            match new_page(self.renderer, self.page_size) {
+                Ok(texture) => self.pages.push(texture),
+                Err(err) => {
+                    self.packers.truncate(self.pages.len());
+                    return Err(err);
+                }
+            }
+        }
+        let area = Rect { up_left, size };
+        upload(&self.pages[page], source, area)?;
+        Ok((page, area))
+    }
+}
+
+fn new_page<'renderer>(
+    renderer: &'renderer Renderer<'renderer>,
+    page_size: Size,
+) -> Result<Texture<'renderer>> {
+    let texture = Texture::with_format(renderer, page_size, ATLAS_FORMAT, TextureAccess::Target)?;
+    texture.set_blend_mode(BlendMode::AlphaBlend)?;
+    clear_page(&texture, page_size)?;
+    Ok(texture)
+}
+
+fn clear_page(texture: &Texture, size: Size) -> Result<()> {
+    let pitch = size.width as usize * 4;
+    let zeros = vec![0u8; pitch * size.height as usize];
+    texture.update(None, &zeros, pitch)
+}
+
+fn upload(texture: &Texture, source: &Owned, area: Rect) -> Result<()> {
+    let raw = unsafe { source.as_ptr().as_ref() };
+    let pitch = raw.pitch as usize;
+    let pixels =
+        unsafe { std::slice::from_raw_parts(raw.pixels.cast::<u8>(), pitch * raw.h as usize) };
+    texture.update(Some(area), pixels, pitch)
+}
+
+impl Paster<'_> {
+    /// Pastes the region in the atlas into `target_area`, or whole if `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `region` was issued by another atlas.
+    pub fn paste_region(
+        &self,
+        atlas: &TextureAtlas,
+        region: AtlasRegion,
+        target_area: Option<Rect>,
+    ) {
+        if !self.renderer().transform().is_identity() {
+            return self.paste_region_f(atlas, region, target_area.map(Into::into));
+        }
+        let (texture, area) = atlas
+            .region_texture(region)
+            .expect("the region must be issued by the atlas");
+        let src = area.into();
+        let dst = target_area.map(Into::into);
+        let ret = unsafe {
+            bind::SDL_RenderCopy(
+                self.renderer().as_ptr(),
+                texture.as_ptr(),
+                &src,
+                as_raw(&dst),
+            )
+        };
+        if ret != 0 {
+            Sdl::error_then_panic("Pasting atlas region to renderer");
+        }
+    }
+
+    /// Pastes the region in the atlas into `target_area` with sub-pixel precision, or whole if `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `region` was issued by another atlas.
+    pub fn paste_region_f(
+        &self,
+        atlas: &TextureAtlas,
+        region: AtlasRegion,
+        target_area: Option<FRect>,
+    ) {
+        let (texture, area) = atlas
+            .region_texture(region)
+            .expect("the region must be issued by the atlas");
+        let target_area = target_area.unwrap_or_else(|| self.whole_target());
+        self.paste_area_f(texture, area, target_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: Rect, b: Rect) -> bool {
+        a.left() < b.right() && b.left() < a.right() && a.top() < b.bottom() && b.top() < a.bottom()
+    }
+
+    #[test]
+    fn packer_fills_row_then_next() {
+        let mut packer = SkylinePacker::new(Size {
+            width: 8,
+            height: 8,
+        });
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        assert_eq!(packer.insert(size), Some(Point { x: 0, y: 0 }));
+        assert_eq!(packer.insert(size), Some(Point { x: 4, y: 0 }));
+        assert_eq!(packer.insert(size), Some(Point { x: 0, y: 4 }));
+        assert_eq!(packer.insert(size), Some(Point { x: 4, y: 4 }));
+        assert_eq!(packer.insert(size), None);
+    }
+
+    #[test]
+    fn packer_rejects_too_large() {
+        let mut packer = SkylinePacker::new(Size {
+            width: 8,
+            height: 8,
+        });
+        assert_eq!(
+            packer.insert(Size {
+                width: 9,
+                height: 1,
+            }),
+            None
+        );
+        assert_eq!(
+            packer.insert(Size {
+                width: 1,
+                height: 9,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn packer_merges_nodes_of_same_height() {
+        let mut packer = SkylinePacker::new(Size {
+            width: 8,
+            height: 8,
+        });
+        let size = Size {
+            width: 4,
+            height: 2,
+        };
+        packer.insert(size);
+        packer.insert(size);
+        assert_eq!(packer.nodes.len(), 1);
+        assert_eq!(
+            (packer.nodes[0].x, packer.nodes[0].y, packer.nodes[0].width),
+            (0, 2, 8)
+        );
+    }
+
+    #[test]
+    fn packer_fits_over_lower_neighbor() {
+        let mut packer = SkylinePacker::new(Size {
+            width: 8,
+            height: 8,
+        });
+        packer.insert(Size {
+            width: 4,
+            height: 4,
+        });
+        packer.insert(Size {
+            width: 4,
+            height: 2,
+        });
+        assert_eq!(
+            packer.insert(Size {
+                width: 8,
+                height: 2,
+            }),
+            Some(Point { x: 0, y: 4 })
+        );
+    }
+
+    #[test]
+    fn plan_places_without_overlaps() {
+        let page_size = Size {
+            width: 16,
+            height: 16,
+        };
+        let sizes: Vec<_> = (1..=12)
+            .map(|i| Size {
+                width: i % 5 + 2,
+                height: i % 7 + 1,
+            })
+            .collect();
+        let (packers, placements) = plan(&sizes, page_size, 1).unwrap();
+        assert_eq!(placements.len(), sizes.len());
+        for (i, &(page, area)) in placements.iter().enumerate() {
+            assert!(page < packers.len());
+            assert_eq!(area.size, sizes[i]);
+            assert!(0 <= area.left() && area.right() <= page_size.width as i32);
+            assert!(0 <= area.top() && area.bottom() <= page_size.height as i32);
+            for &(other_page, other) in &placements[i + 1..] {
+                assert!(page != other_page || !overlaps(area, other));
+            }
+        }
+    }
+
+    #[test]
+    fn plan_adds_pages_when_full() {
+        let page_size = Size {
+            width: 4,
+            height: 4,
+        };
+        let sizes = vec![page_size; 3];
+        let (packers, placements) = plan(&sizes, page_size, 1).unwrap();
+        assert_eq!(packers.len(), 3);
+        let mut pages: Vec<_> = placements.iter().map(|&(page, _)| page).collect();
+        pages.sort_unstable();
+        assert_eq!(pages, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn plan_rejects_larger_than_page() {
+        let page_size = Size {
+            width: 4,
+            height: 4,
+        };
+        let sizes = [Size {
+            width: 5,
+            height: 1,
+        }];
+        assert!(plan(&sizes, page_size, 0).is_err());
+    }
+}
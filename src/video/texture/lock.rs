@@ -3,59 +3,225 @@
 use std::ffi::c_void;
 use std::ptr::NonNull;
 
+use crate::color::pixel::{kind::PixelFormatKind, Pixel, PixelFormat};
+use crate::color::Rgba;
 use crate::{
     as_raw,
-    geo::{Rect, Size},
+    geo::{Point, Rect},
 };
 use crate::{bind, Sdl};
 
 use super::Texture;
 
 /// A lock of the texture, ready to read/write as the raw pixels.
+///
+/// The locked pixels are arranged in rows of [`Lock::pitch`] bytes, which may be longer than the width of the locked area in bytes.
 pub struct Lock<'texture> {
     texture: &'texture mut Texture<'texture>,
     pixels: NonNull<c_void>,
-    len: usize,
+    pitch: usize,
+    area: Rect,
+    kind: PixelFormatKind,
+    format: Option<PixelFormat>,
 }
 
 impl std::fmt::Debug for Lock<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Lock")
             .field("texture", &self.texture)
-            .field("len", &self.len)
+            .field("pitch", &self.pitch)
+            .field("area", &self.area)
+            .field("kind", &self.kind)
             .finish_non_exhaustive()
     }
 }
 
 impl<'texture> Lock<'texture> {
     pub(super) fn new(texture: &'texture mut Texture<'texture>, area: Option<Rect>) -> Self {
-        let area = area.map(Into::into);
+        use super::QueryExt;
+        let area = area.unwrap_or_else(|| Rect {
+            up_left: Point::default(),
+            size: texture.size(),
+        });
+        let kind = texture.format();
+        // SDL2 cannot allocate the format for YUV formats, then only the color helpers are unavailable.
+        let format = PixelFormat::new(kind).ok();
+        let raw_area = Some(area.into());
         let (mut pixels, mut pitch) = (std::ptr::null_mut(), 0);
         let ret = unsafe {
-            bind::SDL_LockTexture(texture.as_ptr(), as_raw(&area), &mut pixels, &mut pitch)
+            bind::SDL_LockTexture(texture.as_ptr(), as_raw(&raw_area), &mut pixels, &mut pitch)
         };
         if ret != 0 {
             Sdl::error_then_panic("Obtaining texture lock");
         }
 
-        use super::QueryExt;
-        let Size { height, .. } = texture.size();
         Self {
             texture,
             pixels: NonNull::new(pixels).unwrap(),
-            len: (pitch as u32 * height) as usize,
+            pitch: pitch as usize,
+            area,
+            kind,
+            format,
+        }
+    }
+
+    /// Returns the number of bytes in a row of the locked pixels.
+    #[must_use]
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    /// Returns the locked area in the texture.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Returns the pixel format of the locked pixels, or `None` if SDL2 does not support the format such as YUV formats.
+    #[must_use]
+    pub fn format(&self) -> Option<&PixelFormat> {
+        self.format.as_ref()
+    }
+
+    /// Returns the pixel format kind of the locked pixels.
+    #[must_use]
+    pub fn format_kind(&self) -> PixelFormatKind {
+        self.kind
+    }
+
+    fn color_format(&self) -> &PixelFormat {
+        self.format
+            .as_ref()
+            .expect("format must be packed or indexed")
+    }
+
+    /// Returns the length from the first pixel to the last one. SDL2 returns the pixels offset into the texture with its whole pitch, so the last row ends at the width of the locked area.
+    fn len(&self) -> usize {
+        let height = self.area.size.height as usize;
+        if height == 0 {
+            return 0;
         }
+        self.pitch * (height - 1) + self.kind.row_bytes(self.area.size.width)
     }
 
     /// Returns bytes of the pixels.
     #[must_use]
     pub fn as_bytes(&self) -> &[u8] {
-        unsafe { std::slice::from_raw_parts(self.pixels.as_ptr().cast(), self.len) }
+        unsafe { std::slice::from_raw_parts(self.pixels.as_ptr().cast(), self.len()) }
     }
 
     /// Returns mutable bytes of the pixels.
     pub fn as_bytes_mut(&mut self) -> &mut [u8] {
-        unsafe { std::slice::from_raw_parts_mut(self.pixels.as_ptr().cast(), self.len) }
+        unsafe { std::slice::from_raw_parts_mut(self.pixels.as_ptr().cast(), self.len()) }
+    }
+
+    /// Returns the iterator of rows of the pixels, each row has [`Lock::pitch`] bytes except the last one cut at the width of the locked area.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.as_bytes().chunks(self.pitch)
+    }
+
+    /// Returns the iterator of mutable rows of the pixels, each row has [`Lock::pitch`] bytes except the last one cut at the width of the locked area.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> + '_ {
+        let pitch = self.pitch;
+        self.as_bytes_mut().chunks_mut(pitch)
+    }
+
+    fn is_u32_compatible(&self) -> bool {
+        self.format
+            .as_ref()
+            .map_or(false, |format| format.bytes_per_pixel() == 4)
+            && self.pitch % 4 == 0
+            && self.pixels.as_ptr() as usize % std::mem::align_of::<u32>() == 0
+    }
+
+    /// Returns the iterator of rows as 32-bit pixels, each row has the width of the locked area. Or returns `None` if the format is not 32-bit.
+    pub fn rows_u32(&self) -> Option<impl Iterator<Item = &[u32]> + '_> {
+        if !self.is_u32_compatible() {
+            return None;
+        }
+        let width = self.area.size.width as usize;
+        let pixels: &[u32] =
+            unsafe { std::slice::from_raw_parts(self.pixels.as_ptr().cast(), self.len() / 4) };
+        Some(pixels.chunks(self.pitch / 4).map(move |row| &row[..width]))
+    }
+
+    /// Returns the iterator of mutable rows as 32-bit pixels, each row has the width of the locked area. Or returns `None` if the format is not 32-bit.
+    pub fn rows_u32_mut(&mut self) -> Option<impl Iterator<Item = &mut [u32]> + '_> {
+        if !self.is_u32_compatible() {
+            return None;
+        }
+        let width = self.area.size.width as usize;
+        let pixels: &mut [u32] =
+            unsafe { std::slice::from_raw_parts_mut(self.pixels.as_ptr().cast(), self.len() / 4) };
+        Some(
+            pixels
+                .chunks_mut(self.pitch / 4)
+                .map(move |row| &mut row[..width]),
+        )
+    }
+
+    /// Returns the color at `pos`, relative to the up left of the locked area.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of the locked area, or the format is not a packed or indexed format.
+    pub fn rgba(&self, pos: Point) -> Rgba {
+        let offset = self.offset_of(pos);
+        let format = self.color_format();
+        let bpp = format.bytes_per_pixel() as usize;
+        let pixel = Pixel::read_bytes(&self.as_bytes()[offset..offset + bpp]);
+        format.rgba_from_pixel(pixel)
+    }
+
+    /// Writes the color at `pos`, relative to the up left of the locked area.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of the locked area, or the format is not a packed or indexed format.
+    pub fn set_rgba(&mut self, pos: Point, color: Rgba) {
+        let offset = self.offset_of(pos);
+        let format = self.color_format();
+        let bpp = format.bytes_per_pixel() as usize;
+        let pixel = format.pixel_by_rgba(color);
+        pixel.write_bytes(&mut self.as_bytes_mut()[offset..offset + bpp]);
+    }
+
+    /// Writes the colors returned from `f` for all the positions in the locked area, relative to the up left of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format is not a packed or indexed format.
+    pub fn fill_with(&mut self, mut f: impl FnMut(Point) -> Rgba) {
+        let format = self.color_format();
+        let bpp = format.bytes_per_pixel() as usize;
+        assert!((1..=4).contains(&bpp), "format must be packed or indexed");
+        let width = self.area.size.width as usize;
+        let pitch = self.pitch;
+        let pixels = unsafe {
+            std::slice::from_raw_parts_mut(self.pixels.as_ptr().cast::<u8>(), self.len())
+        };
+        for (y, row) in pixels.chunks_mut(pitch).enumerate() {
+            for (x, dst) in row[..width * bpp].chunks_mut(bpp).enumerate() {
+                let color = f(Point {
+                    x: x as i32,
+                    y: y as i32,
+                });
+                format.pixel_by_rgba(color).write_bytes(dst);
+            }
+        }
+    }
+
+    fn offset_of(&self, Point { x, y }: Point) -> usize {
+        let bpp = self.color_format().bytes_per_pixel() as usize;
+        assert!((1..=4).contains(&bpp), "format must be packed or indexed");
+        assert!(
+            0 <= x && (x as u32) < self.area.size.width,
+            "x must be in the locked area"
+        );
+        assert!(
+            0 <= y && (y as u32) < self.area.size.height,
+            "y must be in the locked area"
+        );
+        y as usize * self.pitch + x as usize * bpp
     }
 }
 
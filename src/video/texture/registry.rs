@@ -0,0 +1,190 @@
+//! Registry of textures to rebuild after the renderer was reset.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::color::Rgb;
+use crate::event::{render::RenderEvent, EventHandler};
+use crate::renderer::{Frame, Renderer};
+use crate::Result;
+
+use super::{QueryExt, Texture, TextureAccess};
+
+/// A closure building the texture, re-run after the renderer was reset.
+pub type TextureBuilder<'renderer> =
+    Box<dyn FnMut(&'renderer Renderer<'renderer>) -> Result<Texture<'renderer>> + 'renderer>;
+
+/// A key of the texture in [`TextureRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureKey {
+    registry: usize,
+    id: usize,
+}
+
+/// The id of the next registry, to tell which registry a [`TextureKey`] belongs to.
+static NEXT_REGISTRY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A reset of the renderer, queued until the textures are rebuilt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PendingReset {
+    Targets,
+    Device,
+}
+
+impl From<&RenderEvent> for PendingReset {
+    fn from(event: &RenderEvent) -> Self {
+        match event {
+            RenderEvent::TargetsReset { .. } => Self::Targets,
+            RenderEvent::DeviceReset { .. } => Self::Device,
+        }
+    }
+}
+
+struct Entry<'renderer> {
+    texture: Texture<'renderer>,
+    builder: TextureBuilder<'renderer>,
+}
+
+/// A registry owning the textures with their builders, to restore the textures on [`RenderEvent`].
+///
+/// Register [`TextureRegistry::reset_handler`] to [`EventBox::handle_render`](crate::event::EventBox::handle_render), then the resets are queued and the textures are rebuilt on the next [`TextureRegistry::frame`].
+pub struct TextureRegistry<'renderer> {
+    renderer: &'renderer Renderer<'renderer>,
+    id: usize,
+    entries: Vec<Entry<'renderer>>,
+    pending: Rc<Cell<Option<PendingReset>>>,
+}
+
+impl std::fmt::Debug for TextureRegistry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextureRegistry")
+            .field("renderer", &self.renderer)
+            .field("len", &self.entries.len())
+            .field("pending", &self.pending.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'renderer> TextureRegistry<'renderer> {
+    /// Constructs an empty registry on the renderer.
+    #[must_use]
+    pub fn new(renderer: &'renderer Renderer<'renderer>) -> Self {
+        Self {
+            renderer,
+            id: NEXT_REGISTRY_ID.fetch_add(1, Ordering::Relaxed),
+            entries: vec![],
+            pending: Rc::default(),
+        }
+    }
+
+    /// Registers the texture built by `builder`. The builder is called now to build the texture, and again after the renderer was reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `builder` failed to build the texture.
+    pub fn register(
+        &mut self,
+        mut builder: impl FnMut(&'renderer Renderer<'renderer>) -> Result<Texture<'renderer>>
+            + 'renderer,
+    ) -> Result<TextureKey> {
+        let texture = builder(self.renderer)?;
+        self.entries.push(Entry {
+            texture,
+            builder: Box::new(builder),
+        });
+        Ok(TextureKey {
+            registry: self.id,
+            id: self.entries.len() - 1,
+        })
+    }
+
+    /// Returns the texture of the key, or `None` if the key was registered to another registry.
+    #[must_use]
+    pub fn get(&self, key: TextureKey) -> Option<&Texture<'renderer>> {
+        if key.registry != self.id {
+            return None;
+        }
+        self.entries.get(key.id).map(|entry| &entry.texture)
+    }
+
+    /// Returns the mutable texture of the key, or `None` if the key was registered to another registry.
+    #[must_use]
+    pub fn get_mut(&mut self, key: TextureKey) -> Option<&mut Texture<'renderer>> {
+        if key.registry != self.id {
+            return None;
+        }
+        self.entries.get_mut(key.id).map(|entry| &mut entry.texture)
+    }
+
+    /// Returns the handler to register to [`EventBox::handle_render`](crate::event::EventBox::handle_render). The handler queues the reset, and the textures are rebuilt on the next [`TextureRegistry::frame`] or [`TextureRegistry::restore_pending`].
+    #[must_use]
+    pub fn reset_handler<'video>(&self) -> EventHandler<'video, RenderEvent> {
+        let pending = Rc::clone(&self.pending);
+        Box::new(move |event| queue(&pending, event.into()))
+    }
+
+    /// Rebuilds the textures lost by the resets queued by [`TextureRegistry::reset_handler`], if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a builder failed to build the texture. The reset is kept queued then, to retry later.
+    pub fn restore_pending(&mut self) -> Result<()> {
+        let reset = match self.pending.take() {
+            Some(reset) => reset,
+            None => return Ok(()),
+        };
+        self.rebuild(reset).map_err(|err| {
+            queue(&self.pending, reset);
+            err
+        })
+    }
+
+    /// Rebuilds the textures lost by the queued resets, then starts the frame of the renderer, see [`Renderer::frame`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a builder failed to build the texture.
+    pub fn frame(&mut self, background: Option<Rgb>) -> Result<Frame<'renderer>> {
+        self.restore_pending()?;
+        Ok(self.renderer.frame(background))
+    }
+
+    /// Rebuilds the textures lost by the event. [`RenderEvent::TargetsReset`] rebuilds only the render target textures, and [`RenderEvent::DeviceReset`] rebuilds all.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a builder failed to build the texture. The rest of textures are not rebuilt then.
+    pub fn handle(&mut self, event: &RenderEvent) -> Result<()> {
+        self.rebuild(event.into())
+    }
+
+    /// Rebuilds all the textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a builder failed to build the texture. The rest of textures are not rebuilt then.
+    pub fn rebuild_all(&mut self) -> Result<()> {
+        for entry in &mut self.entries {
+            entry.texture = (entry.builder)(self.renderer)?;
+        }
+        Ok(())
+    }
+
+    fn rebuild(&mut self, reset: PendingReset) -> Result<()> {
+        for entry in &mut self.entries {
+            if reset == PendingReset::Targets && entry.texture.access() != TextureAccess::Target {
+                continue;
+            }
+            entry.texture = (entry.builder)(self.renderer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues the reset, merging with the queued one. [`PendingReset::Device`] covers [`PendingReset::Targets`].
+fn queue(pending: &Cell<Option<PendingReset>>, reset: PendingReset) {
+    pending.set(Some(
+        pending.get().map_or(reset, |queued| queued.max(reset)),
+    ));
+}